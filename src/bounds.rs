@@ -0,0 +1,207 @@
+use crate::{Number, Tableau, Tolerances};
+use matrix::format::Compressed;
+
+/// Bounds of every tableau column, so bounded variables don't need rows of their own.
+///
+/// A column doesn't hold its variable directly but the distance of the variable from one of its
+/// bounds, from the lower bound normally and from the upper bound once the column is `flipped`.
+/// Either way the column ranges from zero to `upper - lower`, and non-basic columns are zero, so
+/// a non-basic variable sits at one of its bounds.
+#[derive(Clone, Debug)]
+pub struct Bounds<T = f32> {
+	pub lower: Vec<T>,
+	/// May be infinite.
+	pub upper: Vec<T>,
+	pub flipped: Vec<bool>,
+}
+
+impl<T: Number> Bounds<T> {
+	/// Bounds for columns that are only required to be non-negative.
+	pub fn free(columns: usize) -> Self {
+		Bounds {
+			lower: vec![T::zero(); columns],
+			upper: vec![T::infinity(); columns],
+			flipped: vec![false; columns],
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.lower.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.lower.is_empty()
+	}
+
+	/// Adds non-negative columns or removes columns from the end.
+	pub fn resize(&mut self, columns: usize) {
+		self.lower.resize(columns, T::zero());
+		self.upper.resize(columns, T::infinity());
+		self.flipped.resize(columns, false);
+	}
+
+	/// Largest value the column itself can take.
+	pub fn range(&self, column: usize) -> T {
+		self.upper[column] - self.lower[column]
+	}
+
+	/// Value of the variable behind the column, given the value of the column.
+	pub fn value(&self, column: usize, internal: T) -> T {
+		if self.flipped[column] {
+			self.upper[column] - internal
+		} else {
+			self.lower[column] + internal
+		}
+	}
+}
+
+/// Discovers the column basic in each row by scanning for unit columns with a zero objective
+/// coefficient, for seeding the basis header of a tableau built by hand, see
+/// [`crate::Tableau::basis`]. When several qualify for the same row, such as items of equal weight
+/// in a knapsack, the one with the widest range is taken since it holds the row's value whenever
+/// any of them can.
+pub fn get_basis<T: Number>(
+	con_coef: &Compressed<T>,
+	obj_coef: &Compressed<T>,
+	bounds: &Bounds<T>,
+	tolerances: &Tolerances,
+) -> Vec<Option<usize>> {
+	let mut basis: Vec<Option<usize>> = vec![None; con_coef.rows];
+	for j in 0..con_coef.columns {
+		if obj_coef.get((0, j)).abs() > T::tolerance(tolerances.optimality) {
+			continue;
+		}
+
+		if let Some(row) = get_basic_row(con_coef, j, tolerances)
+			&& basis[row].is_none_or(|k| bounds.range(j) > bounds.range(k))
+		{
+			basis[row] = Some(j);
+		}
+	}
+
+	basis
+}
+
+/// Values of the variables behind every column, basic columns take their right-hand side value and
+/// non-basic columns sit at the bound they are measured from. `basis` holds the basic column of
+/// every row.
+pub fn get_bounded_values<T: Number>(
+	con_rhs_coef: &Compressed<T>,
+	bounds: &Bounds<T>,
+	basis: &[Option<usize>],
+) -> Vec<T> {
+	let mut internal = vec![T::zero(); bounds.len()];
+	for (i, &column) in basis.iter().enumerate() {
+		if let Some(j) = column {
+			internal[j] = con_rhs_coef.get((i, 0));
+		}
+	}
+
+	internal
+		.into_iter()
+		.enumerate()
+		.map(|(j, value)| bounds.value(j, value))
+		.collect()
+}
+
+/// Row the column is a unit column in, i.e. the row with its one when it is zero everywhere else.
+pub(crate) fn get_basic_row<T: Number>(
+	con_coef: &Compressed<T>,
+	column: usize,
+	tolerances: &Tolerances,
+) -> Option<usize> {
+	let zero = T::tolerance(tolerances.pivot);
+	let mut basic_row = None;
+	for i in 0..con_coef.rows {
+		let coef = con_coef.get((i, column));
+		if (coef - T::one()).abs() <= zero && basic_row.is_none() {
+			basic_row = Some(i);
+		} else if coef.abs() > zero {
+			return None;
+		}
+	}
+
+	basic_row
+}
+
+/// Substitutes `column + delta` for the column, so it is measured from `delta` further along.
+fn shift_column<T: Number>(tableau: &mut Tableau<T>, column: usize, delta: T) {
+	if delta.is_zero() {
+		return;
+	}
+
+	for i in 0..tableau.con_coef.rows {
+		let coef = tableau.con_coef.get((i, column));
+		if !coef.is_zero() {
+			let rhs = tableau.con_rhs_coef.get((i, 0));
+			tableau.con_rhs_coef.set((i, 0), rhs - coef * delta);
+		}
+	}
+
+	let rhs = tableau.obj_rhs.get((0, 0));
+	let coef = tableau.obj_coef.get((0, column));
+	tableau.obj_rhs.set((0, 0), rhs - coef * delta);
+}
+
+/// Switches the bound a column is measured from, substituting `range - column` for the column.
+/// Non-basic columns move their variable to the other bound, basic columns keep their value and
+/// their place in the basis.
+///
+/// # Panics
+///
+/// Panics if the column has no finite range.
+pub fn complement_column<T: Number>(tableau: &mut Tableau<T>, column: usize) {
+	let range = tableau.bounds.range(column);
+	assert!(
+		range.is_finite(),
+		"only bounded columns can be complemented"
+	);
+
+	let basic_row = tableau
+		.basis
+		.iter()
+		.position(|&basic| basic == Some(column));
+
+	// measuring from range first and then negating the column is the same as range - column
+	shift_column(tableau, column, range);
+	let con_coef = &mut tableau.con_coef;
+	for i in 0..con_coef.rows {
+		let coef = con_coef.get((i, column));
+		if !coef.is_zero() {
+			con_coef.set((i, column), -coef);
+		}
+	}
+
+	let coef = tableau.obj_coef.get((0, column));
+	tableau.obj_coef.set((0, column), -coef);
+
+	// the basic column is now a negative unit column, negating its row restores it
+	if let Some(row) = basic_row {
+		for j in 0..con_coef.columns {
+			let coef = con_coef.get((row, j));
+			if !coef.is_zero() {
+				con_coef.set((row, j), -coef);
+			}
+		}
+
+		let rhs = tableau.con_rhs_coef.get((row, 0));
+		tableau.con_rhs_coef.set((row, 0), -rhs);
+	}
+
+	tableau.bounds.flipped[column] = !tableau.bounds.flipped[column];
+}
+
+/// Changes the bounds of a variable in place, keeping the column measured from the same bound.
+/// Basic variables may end up outside their new bounds, which the dual simplex repairs.
+pub fn set_column_bounds<T: Number>(tableau: &mut Tableau<T>, column: usize, lower: T, upper: T) {
+	let bounds = &tableau.bounds;
+	let delta = if bounds.flipped[column] {
+		bounds.upper[column] - upper
+	} else {
+		lower - bounds.lower[column]
+	};
+
+	shift_column(tableau, column, delta);
+	tableau.bounds.lower[column] = lower;
+	tableau.bounds.upper[column] = upper;
+}
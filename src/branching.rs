@@ -0,0 +1,283 @@
+use crate::Number;
+use std::{fmt, sync::Arc};
+
+/// A basic variable with a fractional value that can be branched on.
+#[derive(Clone, Copy, Debug)]
+pub struct Candidate<T = f32> {
+	/// Column of the variable in the tableau.
+	pub var: usize,
+	pub value: T,
+}
+
+impl<T: Number> Candidate<T> {
+	pub fn fraction(&self) -> T {
+		self.value - self.value.floor()
+	}
+}
+
+/// Objective values of the two children of a branch, `None` when a child is infeasible.
+#[derive(Clone, Copy, Debug)]
+pub struct ChildBounds {
+	/// Child with the `x ≤ floor(value)` bound.
+	pub down: Option<f32>,
+	/// Child with the `x ≥ ceil(value)` bound.
+	pub up: Option<f32>,
+}
+
+/// Decides which fractional variable a node is branched on.
+pub trait BranchRule {
+	/// Returns the index of the chosen candidate, `candidates` is never empty. `probe` solves the
+	/// children of a candidate without adding them to the tree, which is expensive.
+	fn select(
+		&mut self,
+		parent_bound: f32,
+		candidates: &[Candidate],
+		probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize;
+
+	/// Called once the children of a node have been solved.
+	fn observe(&mut self, _parent_bound: f32, _candidate: &Candidate, _children: &ChildBounds) {}
+}
+
+/// Picks the candidate whose value is closest to rounding down.
+#[derive(Default)]
+pub struct SmallestFraction;
+
+impl BranchRule for SmallestFraction {
+	fn select(
+		&mut self,
+		_parent_bound: f32,
+		candidates: &[Candidate],
+		_probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize {
+		argmax(candidates, |c| -c.fraction())
+	}
+}
+
+/// Picks the candidate whose fractional part is closest to one half.
+#[derive(Default)]
+pub struct MostFractional;
+
+impl BranchRule for MostFractional {
+	fn select(
+		&mut self,
+		_parent_bound: f32,
+		candidates: &[Candidate],
+		_probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize {
+		argmax(candidates, |c| c.fraction().min(1.0 - c.fraction()))
+	}
+}
+
+/// Picks the fractional candidate with the lowest column index.
+#[derive(Default)]
+pub struct FirstFractional;
+
+impl BranchRule for FirstFractional {
+	fn select(
+		&mut self,
+		_parent_bound: f32,
+		candidates: &[Candidate],
+		_probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize {
+		argmax(candidates, |c| -(c.var as f32))
+	}
+}
+
+/// Knapsack rule picking the candidate with the best value to weight ratio.
+pub struct Ratio {
+	ratios: Vec<f32>,
+}
+
+impl Ratio {
+	/// `values` and `weights` are indexed by `x` variable.
+	pub fn new(values: &[f32], weights: &[f32]) -> Self {
+		Ratio {
+			ratios: values.iter().zip(weights).map(|(v, w)| v / w).collect(),
+		}
+	}
+}
+
+impl BranchRule for Ratio {
+	fn select(
+		&mut self,
+		_parent_bound: f32,
+		candidates: &[Candidate],
+		_probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize {
+		argmax(candidates, |c| {
+			self.ratios.get(c.var).copied().unwrap_or(f32::NEG_INFINITY)
+		})
+	}
+}
+
+/// Scores candidates by the average objective loss per unit change seen when branching on them
+/// earlier. Variables that were never branched on use the average over all variables.
+#[derive(Default)]
+pub struct Pseudocost {
+	down: Vec<(f32, usize)>,
+	up: Vec<(f32, usize)>,
+}
+
+impl Pseudocost {
+	fn cost(history: &[(f32, usize)], var: usize) -> f32 {
+		match history.get(var) {
+			Some(&(sum, count)) if count > 0 => sum / count as f32,
+			_ => {
+				let (sum, count) = history
+					.iter()
+					.filter(|(_, count)| *count > 0)
+					.fold((0.0, 0), |(s, c), &(sum, count)| {
+						(s + sum / count as f32, c + 1)
+					});
+				if count > 0 { sum / count as f32 } else { 1.0 }
+			}
+		}
+	}
+
+	fn record(history: &mut Vec<(f32, usize)>, var: usize, unit_loss: f32) {
+		if history.len() <= var {
+			history.resize(var + 1, (0.0, 0));
+		}
+
+		history[var].0 += unit_loss;
+		history[var].1 += 1;
+	}
+}
+
+impl BranchRule for Pseudocost {
+	fn select(
+		&mut self,
+		_parent_bound: f32,
+		candidates: &[Candidate],
+		_probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize {
+		argmax(candidates, |c| {
+			let down = Self::cost(&self.down, c.var) * c.fraction();
+			let up = Self::cost(&self.up, c.var) * (1.0 - c.fraction());
+			score(down, up)
+		})
+	}
+
+	fn observe(&mut self, parent_bound: f32, candidate: &Candidate, children: &ChildBounds) {
+		// infeasible children say nothing about the cost of moving the variable
+		if let Some(down) = children.down {
+			let loss = (parent_bound - down).max(0.0) / candidate.fraction();
+			Self::record(&mut self.down, candidate.var, loss);
+		}
+
+		if let Some(up) = children.up {
+			let loss = (parent_bound - up).max(0.0) / (1.0 - candidate.fraction());
+			Self::record(&mut self.up, candidate.var, loss);
+		}
+	}
+}
+
+/// Solves the children of the most fractional candidates and picks the one whose children lose the
+/// most objective value.
+pub struct Strong {
+	max_candidates: usize,
+}
+
+impl Strong {
+	/// Only the `max_candidates` most fractional candidates are probed.
+	pub fn new(max_candidates: usize) -> Self {
+		Strong {
+			max_candidates: max_candidates.max(1),
+		}
+	}
+}
+
+impl BranchRule for Strong {
+	fn select(
+		&mut self,
+		parent_bound: f32,
+		candidates: &[Candidate],
+		probe: &mut dyn FnMut(&Candidate) -> ChildBounds,
+	) -> usize {
+		let mut order = (0..candidates.len()).collect::<Vec<_>>();
+		order.sort_by(|&a, &b| {
+			let a = candidates[a].fraction().min(1.0 - candidates[a].fraction());
+			let b = candidates[b].fraction().min(1.0 - candidates[b].fraction());
+			b.total_cmp(&a)
+		});
+
+		let mut best = (order[0], f32::NEG_INFINITY);
+		for &i in order.iter().take(self.max_candidates) {
+			let children = probe(&candidates[i]);
+			// an infeasible child closes that side entirely, which is as good as it gets
+			let loss = |bound: Option<f32>| bound.map_or(f32::INFINITY, |b| parent_bound - b);
+			let candidate_score = score(loss(children.down), loss(children.up));
+			if candidate_score > best.1 {
+				best = (i, candidate_score);
+			}
+		}
+
+		best.0
+	}
+}
+
+/// Product score favouring candidates where both children lose objective value.
+fn score(down: f32, up: f32) -> f32 {
+	down.max(1e-6) * up.max(1e-6)
+}
+
+/// Index of the candidate with the highest key, ties go to the first candidate.
+fn argmax(candidates: &[Candidate], key: impl Fn(&Candidate) -> f32) -> usize {
+	let mut best = (0, f32::NEG_INFINITY);
+	for (i, candidate) in candidates.iter().enumerate() {
+		let value = key(candidate);
+		if value > best.1 {
+			best = (i, value);
+		}
+	}
+
+	best.0
+}
+
+/// Branching rule used by [`crate::branch_and_bound`].
+#[derive(Clone, Default)]
+pub enum Branching {
+	SmallestFraction,
+	#[default]
+	MostFractional,
+	FirstFractional,
+	Ratio {
+		values: Vec<f32>,
+		weights: Vec<f32>,
+	},
+	Pseudocost,
+	Strong {
+		max_candidates: usize,
+	},
+	/// Creates a user supplied rule for every solve.
+	Custom(Arc<dyn Fn() -> Box<dyn BranchRule> + Send + Sync>),
+}
+
+impl Branching {
+	pub(crate) fn build(&self) -> Box<dyn BranchRule> {
+		match self {
+			Branching::SmallestFraction => Box::new(SmallestFraction),
+			Branching::MostFractional => Box::new(MostFractional),
+			Branching::FirstFractional => Box::new(FirstFractional),
+			Branching::Ratio { values, weights } => Box::new(Ratio::new(values, weights)),
+			Branching::Pseudocost => Box::new(Pseudocost::default()),
+			Branching::Strong { max_candidates } => Box::new(Strong::new(*max_candidates)),
+			Branching::Custom(factory) => factory(),
+		}
+	}
+}
+
+impl fmt::Debug for Branching {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Branching::SmallestFraction => write!(f, "SmallestFraction"),
+			Branching::MostFractional => write!(f, "MostFractional"),
+			Branching::FirstFractional => write!(f, "FirstFractional"),
+			Branching::Ratio { .. } => write!(f, "Ratio"),
+			Branching::Pseudocost => write!(f, "Pseudocost"),
+			Branching::Strong { max_candidates } => write!(f, "Strong({max_candidates})"),
+			Branching::Custom(_) => write!(f, "Custom"),
+		}
+	}
+}
@@ -0,0 +1,27 @@
+use std::sync::{
+	Arc,
+	atomic::{AtomicBool, Ordering},
+};
+
+/// Handle for stopping a solve from another thread. Clones share the same flag, so a clone kept by
+/// the caller cancels the solve holding the original, see [`crate::SolverOptions::cancel`].
+///
+/// The simplex checks the flag before every pivot and branch and bound before every node, a
+/// cancelled solve returns the incumbent found so far with [`crate::SolutionStatus::Cancelled`].
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+	pub fn new() -> Self {
+		CancelToken::default()
+	}
+
+	/// Asks every solve holding a clone of the token to stop as soon as it can.
+	pub fn cancel(&self) {
+		self.0.store(true, Ordering::Relaxed);
+	}
+
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Relaxed)
+	}
+}
@@ -0,0 +1,155 @@
+use crate::{BranchDirection, Number, PruneReason, SolverEvent, SolverObserver};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug)]
+enum Fate {
+	Branched,
+	Pruned(PruneReason),
+}
+
+#[derive(Clone, Debug)]
+struct TreeNode {
+	section: String,
+	/// Bound added by the branch leading to the node, e.g. `x4 ≤ 0`.
+	branch: Option<String>,
+	/// Relaxation bound, already formatted.
+	bound: Option<String>,
+	fate: Option<Fate>,
+}
+
+/// Collects the branch and bound tree from solver events and prints it as a Graphviz DOT graph,
+/// with every node showing its branching bound, relaxation bound and fate. The node the final
+/// solution came from is highlighted.
+#[derive(Clone, Debug, Default)]
+pub struct DotTree {
+	nodes: Vec<TreeNode>,
+	incumbent: Option<String>,
+}
+
+impl DotTree {
+	pub fn new() -> Self {
+		DotTree::default()
+	}
+
+	fn node(&mut self, section: &str) -> &mut TreeNode {
+		// children are usually reported right after they are created, so search from the back
+		let index = match self.nodes.iter().rposition(|n| n.section == section) {
+			Some(index) => index,
+			None => {
+				self.nodes.push(TreeNode {
+					section: section.to_string(),
+					branch: None,
+					bound: None,
+					fate: None,
+				});
+				self.nodes.len() - 1
+			}
+		};
+
+		&mut self.nodes[index]
+	}
+}
+
+impl<T: Number> SolverObserver<T> for DotTree {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		match *event {
+			SolverEvent::NodeOpened { section, bound, .. } => {
+				self
+					.node(section)
+					.bound
+					.get_or_insert_with(|| format!("{bound:.3}"));
+			}
+			SolverEvent::Branching { section, .. } => {
+				self.node(section).fate = Some(Fate::Branched);
+			}
+			SolverEvent::BranchCreated {
+				section,
+				variable,
+				direction,
+				bound,
+				..
+			} => {
+				let relation = match direction {
+					BranchDirection::Down => "≤",
+					BranchDirection::Up => "≥",
+				};
+
+				self.node(section).branch = Some(format!("{variable} {relation} {bound}"));
+			}
+			SolverEvent::LpSolved {
+				section, objective, ..
+			} => {
+				self.node(section).bound = Some(format!("{objective:.3}"));
+			}
+			SolverEvent::NodePruned {
+				section, reason, ..
+			} => {
+				self.node(section).fate = Some(Fate::Pruned(*reason));
+			}
+			SolverEvent::Finished { solution } => {
+				self.incumbent = (!solution.section.is_empty()).then(|| solution.section.clone());
+			}
+			_ => {}
+		}
+	}
+}
+
+impl fmt::Display for DotTree {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "digraph tree {{")?;
+		writeln!(f, "\tnode [shape=box];")?;
+
+		for node in &self.nodes {
+			let mut label = node.section.clone();
+			if let Some(branch) = &node.branch {
+				label += &format!("\n{branch}");
+			}
+
+			if let Some(bound) = &node.bound {
+				label += &format!("\nz = {bound}");
+			}
+
+			let (fate, style) = match node.fate {
+				Some(Fate::Branched) => ("branched", ""),
+				Some(Fate::Pruned(PruneReason::Integral)) => ("integral", ", color=darkgreen"),
+				Some(Fate::Pruned(PruneReason::Bound)) => ("pruned", ", color=gray, fontcolor=gray"),
+				Some(Fate::Pruned(PruneReason::Infeasible)) => ("infeasible", ", color=red"),
+				Some(Fate::Pruned(PruneReason::Failed(_))) => ("failed", ", color=red, style=dashed"),
+				None => ("open", ", style=dotted"),
+			};
+
+			label += &format!("\n{fate}");
+			let highlight = if self.incumbent.as_ref() == Some(&node.section) {
+				", style=\"filled,bold\", fillcolor=gold"
+			} else {
+				""
+			};
+
+			writeln!(
+				f,
+				"\t\"{}\" [label=\"{}\"{style}{highlight}];",
+				escape(&node.section),
+				escape(&label)
+			)?;
+
+			if let Some((parent, _)) = node.section.rsplit_once('.') {
+				writeln!(
+					f,
+					"\t\"{}\" -> \"{}\";",
+					escape(parent),
+					escape(&node.section)
+				)?;
+			}
+		}
+
+		writeln!(f, "}}")
+	}
+}
+
+/// Escapes a string for use inside a quoted DOT identifier.
+fn escape(value: &str) -> String {
+	value
+		.replace('\\', "\\\\")
+		.replace('"', "\\\"")
+		.replace('\n', "\\n")
+}
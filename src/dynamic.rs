@@ -0,0 +1,240 @@
+use crate::{Solution, SolutionStatus};
+
+/// Exact 0/1 knapsack by dynamic programming over the capacity, in `O(n · capacity)` time.
+///
+/// Besides one best value per capacity, a bit per item and capacity records whether the item was
+/// taken, which is what the items are reconstructed from. See [`knapsack_dp_lean`] when those
+/// `n · capacity` bits don't fit in memory.
+///
+/// # Panics
+///
+/// Panics if `values` and `weights` have different lengths.
+pub fn knapsack_dp(values: &[f32], weights: &[usize], capacity: usize) -> Solution {
+	assert_eq!(
+		values.len(),
+		weights.len(),
+		"every item needs both a value and a weight"
+	);
+
+	let width = capacity + 1;
+	let mut best = vec![0.0; width];
+	let mut taken = vec![0u64; (values.len() * width).div_ceil(64)];
+
+	for (i, (&value, &weight)) in values.iter().zip(weights).enumerate() {
+		// going down keeps every item from being picked twice
+		for c in (weight..width).rev() {
+			let with_item = best[c - weight] + value;
+			if with_item > best[c] {
+				best[c] = with_item;
+				let bit = i * width + c;
+				taken[bit / 64] |= 1 << (bit % 64);
+			}
+		}
+	}
+
+	let mut picked = vec![false; values.len()];
+	let mut c = capacity;
+	for i in (0..values.len()).rev() {
+		let bit = i * width + c;
+		if taken[bit / 64] & (1 << (bit % 64)) != 0 {
+			picked[i] = true;
+			c -= weights[i];
+		}
+	}
+
+	solution(values, &picked)
+}
+
+/// Like [`knapsack_dp`], but only keeps `O(capacity)` values around at any time.
+///
+/// Items are split in half Hirschberg style, the best value of each half is computed for every
+/// capacity and the capacity split with the best total decides how much of the capacity each half
+/// gets, after which both halves are solved on their own. This costs a factor of `log n` in time.
+///
+/// # Panics
+///
+/// Panics if `values` and `weights` have different lengths.
+pub fn knapsack_dp_lean(values: &[f32], weights: &[usize], capacity: usize) -> Solution {
+	assert_eq!(
+		values.len(),
+		weights.len(),
+		"every item needs both a value and a weight"
+	);
+
+	let mut picked = vec![false; values.len()];
+	split(values, weights, capacity, 0, &mut picked);
+	solution(values, &picked)
+}
+
+/// Picks the items of `values[offset..]` for the given capacity.
+fn split(values: &[f32], weights: &[usize], capacity: usize, offset: usize, picked: &mut [bool]) {
+	match values.len() {
+		0 => {}
+		1 => picked[offset] = weights[0] <= capacity && values[0] > 0.0,
+		n => {
+			let mid = n / 2;
+			// both tables are dropped before recursing, so only one level holds any at a time
+			let left_capacity = {
+				let left = best_values(&values[..mid], &weights[..mid], capacity);
+				let right = best_values(&values[mid..], &weights[mid..], capacity);
+
+				// first capacity split with the best total, ties go to the smaller left capacity
+				let total = |c: usize| left[c] + right[capacity - c];
+				let mut left_capacity = 0;
+				for c in 1..=capacity {
+					if total(c) > total(left_capacity) {
+						left_capacity = c;
+					}
+				}
+
+				left_capacity
+			};
+
+			split(
+				&values[..mid],
+				&weights[..mid],
+				left_capacity,
+				offset,
+				picked,
+			);
+			split(
+				&values[mid..],
+				&weights[mid..],
+				capacity - left_capacity,
+				offset + mid,
+				picked,
+			);
+		}
+	}
+}
+
+/// Best total value of the items for every capacity up to `capacity`.
+fn best_values(values: &[f32], weights: &[usize], capacity: usize) -> Vec<f32> {
+	let mut best = vec![0.0f32; capacity + 1];
+	for (&value, &weight) in values.iter().zip(weights) {
+		for c in (weight..=capacity).rev() {
+			best[c] = best[c].max(best[c - weight] + value);
+		}
+	}
+
+	best
+}
+
+/// Solution with variables named `x1`, `x2`, ... like [`crate::Knapsack::model`].
+fn solution(values: &[f32], picked: &[bool]) -> Solution {
+	// folded from zero since an empty f32 sum is -0
+	let objective = values
+		.iter()
+		.zip(picked)
+		.filter(|(_, picked)| **picked)
+		.fold(0.0, |total, (value, _)| total + value);
+
+	Solution {
+		status: SolutionStatus::Optimal,
+		objective,
+		bound: objective,
+		values: picked.iter().map(|&p| if p { 1.0 } else { 0.0 }).collect(),
+		names: (1..=values.len()).map(|i| format!("x{i}")).collect(),
+		section: String::new(),
+		pruned: Vec::new(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{DynamicProgramming, DynamicProgrammingLean, Knapsack, KnapsackSolver};
+
+	/// Best total value over every subset of items.
+	fn brute_force(values: &[f32], weights: &[usize], capacity: usize) -> f32 {
+		(0..1u32 << values.len())
+			.filter(|set| {
+				let weight = (0..values.len())
+					.filter(|i| set & 1 << i != 0)
+					.map(|i| weights[i])
+					.sum::<usize>();
+				weight <= capacity
+			})
+			.map(|set| {
+				(0..values.len())
+					.filter(|i| set & 1 << i != 0)
+					.map(|i| values[i])
+					.sum::<f32>()
+			})
+			.fold(0.0, f32::max)
+	}
+
+	fn assert_feasible(solution: &Solution, values: &[f32], weights: &[usize], capacity: usize) {
+		let picked = solution.values.iter().map(|&v| v == 1.0);
+		let (weight, value) = picked
+			.zip(weights.iter().zip(values))
+			.filter(|(picked, _)| *picked)
+			.fold((0, 0.0), |(w, v), (_, (weight, value))| {
+				(w + weight, v + value)
+			});
+		assert!(weight <= capacity);
+		assert_eq!(value, solution.objective);
+	}
+
+	#[test]
+	fn readme_example() {
+		let values = [2.0, 3.0, 3.0, 5.0, 2.0, 4.0];
+		let weights = [11, 8, 6, 14, 10, 10];
+		for solution in [
+			knapsack_dp(&values, &weights, 40),
+			knapsack_dp_lean(&values, &weights, 40),
+		] {
+			assert_eq!(solution.objective, 15.0);
+			assert_eq!(solution.values, [0.0, 1.0, 1.0, 1.0, 0.0, 1.0]);
+		}
+	}
+
+	#[test]
+	fn near_integer_weights_fall_back_to_branch_and_bound() {
+		// rounding these weights to 20 would let both heavy items fit, 0.00002 over capacity
+		let knapsack = Knapsack::new(vec![5.0, 5.0, 1.0], vec![20.00001, 20.00001, 1.0], 40.0);
+		assert!(!DynamicProgramming.supports(&knapsack));
+		assert!(!DynamicProgrammingLean.supports(&knapsack));
+
+		let solution = knapsack.solve();
+		assert_eq!(solution.status, SolutionStatus::Optimal);
+		assert!((solution.objective - 6.0).abs() < 1e-4);
+		assert_eq!(solution.values, [1.0, 0.0, 1.0]);
+	}
+
+	#[test]
+	fn lean_matches_full_table() {
+		// xorshift, so the instances are the same on every run
+		let mut state = 0x2545_f491_u64;
+		let mut next = |bound: u64| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state % bound
+		};
+
+		for _ in 0..300 {
+			let n = next(12) as usize;
+			let values = (0..n).map(|_| next(20) as f32).collect::<Vec<_>>();
+			let weights = (0..n).map(|_| next(15) as usize).collect::<Vec<_>>();
+			let capacity = next(40) as usize;
+
+			let full = knapsack_dp(&values, &weights, capacity);
+			let lean = knapsack_dp_lean(&values, &weights, capacity);
+			assert_eq!(full.objective, brute_force(&values, &weights, capacity));
+			assert_eq!(lean.objective, full.objective);
+			assert_feasible(&full, &values, &weights, capacity);
+			assert_feasible(&lean, &values, &weights, capacity);
+		}
+	}
+
+	#[test]
+	fn empty_and_zero_capacity() {
+		assert_eq!(knapsack_dp(&[], &[], 10).objective, 0.0);
+		assert_eq!(knapsack_dp_lean(&[], &[], 10).objective, 0.0);
+
+		let solution = knapsack_dp_lean(&[3.0, 4.0], &[0, 1], 0);
+		assert_eq!(solution.objective, 3.0);
+		assert_eq!(solution.values, [1.0, 0.0]);
+	}
+}
@@ -0,0 +1,81 @@
+use std::{error::Error, fmt};
+
+/// Why the simplex could not produce an optimal tableau. Rows and columns are zero based and
+/// `iterations` counts the pivots performed before the failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveError {
+	/// No row limits the entering column, so the primal objective can grow without bound.
+	Unbounded { column: usize, iterations: usize },
+	/// No column can enter for a row with a negative right-hand side, so the dual is unbounded and
+	/// the primal has no feasible solution.
+	Infeasible { row: usize, iterations: usize },
+	/// The simplex gave up after pivoting too many times, usually because it is cycling.
+	IterationLimit { iterations: usize },
+	/// The solve was cancelled through its [`crate::CancelToken`].
+	Cancelled { iterations: usize },
+	/// The pivot element was too close to zero or the tableau stopped being finite.
+	Numerical {
+		row: usize,
+		column: usize,
+		iterations: usize,
+	},
+}
+
+impl SolveError {
+	/// Whether the simplex stopped before it could tell anything about the relaxation, at the
+	/// pivot limit or because it was cancelled.
+	pub fn is_interrupted(&self) -> bool {
+		matches!(
+			self,
+			SolveError::IterationLimit { .. } | SolveError::Cancelled { .. }
+		)
+	}
+
+	pub fn iterations(&self) -> usize {
+		match *self {
+			SolveError::Unbounded { iterations, .. }
+			| SolveError::Infeasible { iterations, .. }
+			| SolveError::IterationLimit { iterations }
+			| SolveError::Cancelled { iterations }
+			| SolveError::Numerical { iterations, .. } => iterations,
+		}
+	}
+}
+
+impl fmt::Display for SolveError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			SolveError::Unbounded { column, iterations } => write!(
+				f,
+				"Unbounded, no leaving row for entering column {} after {} pivots",
+				column + 1,
+				iterations
+			),
+			SolveError::Infeasible { row, iterations } => write!(
+				f,
+				"Infeasible, no entering column for leaving row {} after {} pivots",
+				row + 1,
+				iterations
+			),
+			SolveError::IterationLimit { iterations } => {
+				write!(f, "Iteration limit reached after {} pivots", iterations)
+			}
+			SolveError::Cancelled { iterations } => {
+				write!(f, "Cancelled after {} pivots", iterations)
+			}
+			SolveError::Numerical {
+				row,
+				column,
+				iterations,
+			} => write!(
+				f,
+				"Numerical breakdown pivoting on row {} column {} after {} pivots",
+				row + 1,
+				column + 1,
+				iterations
+			),
+		}
+	}
+}
+
+impl Error for SolveError {}
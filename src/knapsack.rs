@@ -0,0 +1,260 @@
+use crate::{
+	BranchAndBound, DynamicProgramming, DynamicProgrammingLean, KnapsackSolver, Model, Relation,
+	Sense, Solution, SolverObserver, SolverOptions, Tableau, TextLog,
+};
+use std::io::{self, Write};
+
+/// DP tables with more bits than this are left to [`KnapsackMethod::DynamicProgrammingLean`] by
+/// [`KnapsackMethod::Auto`], 32 MiB worth.
+const DP_TABLE_BITS: usize = 1 << 28;
+
+/// Largest capacity [`KnapsackMethod::Auto`] still solves by dynamic programming.
+const DP_MAX_CAPACITY: usize = 1 << 24;
+
+/// Algorithm used by [`Knapsack::solve`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KnapsackMethod {
+	/// Dynamic programming when the weights and capacity are small non-negative integers, branch
+	/// and bound otherwise.
+	#[default]
+	Auto,
+	/// See [`crate::BranchAndBound`].
+	BranchAndBound,
+	/// See [`crate::DynamicProgramming`].
+	DynamicProgramming,
+	/// See [`crate::DynamicProgrammingLean`].
+	DynamicProgrammingLean,
+}
+
+/// 0/1 knapsack problem, maximising the total value of the picked items without their total weight
+/// exceeding the capacity.
+#[derive(Clone, Debug)]
+pub struct Knapsack {
+	values: Vec<f32>,
+	weights: Vec<f32>,
+	capacity: f32,
+	options: SolverOptions,
+	method: KnapsackMethod,
+}
+
+impl Knapsack {
+	/// # Panics
+	///
+	/// Panics if `values` and `weights` have different lengths.
+	pub fn new(values: Vec<f32>, weights: Vec<f32>, capacity: f32) -> Self {
+		assert_eq!(
+			values.len(),
+			weights.len(),
+			"every item needs both a value and a weight"
+		);
+
+		Knapsack {
+			values,
+			weights,
+			capacity,
+			options: SolverOptions::default(),
+			method: KnapsackMethod::default(),
+		}
+	}
+
+	pub fn with_options(mut self, options: SolverOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	pub fn with_method(mut self, method: KnapsackMethod) -> Self {
+		self.method = method;
+		self
+	}
+
+	pub fn values(&self) -> &[f32] {
+		&self.values
+	}
+
+	pub fn weights(&self) -> &[f32] {
+		&self.weights
+	}
+
+	pub fn capacity(&self) -> f32 {
+		self.capacity
+	}
+
+	pub fn options(&self) -> &SolverOptions {
+		&self.options
+	}
+
+	/// Weights and capacity as integers, if they all are non-negative integers. Only exact integers
+	/// count, rounding weights that are merely close would solve a different problem.
+	pub(crate) fn integer_weights(&self) -> Option<(Vec<usize>, usize)> {
+		let integer = |value: f32| {
+			(value >= 0.0 && value < usize::MAX as f32 && value.fract() == 0.0).then_some(value as usize)
+		};
+
+		let weights = self
+			.weights
+			.iter()
+			.map(|&w| integer(w))
+			.collect::<Option<Vec<_>>>()?;
+		Some((weights, integer(self.capacity)?))
+	}
+
+	/// Model with a binary variable `x1`, `x2`, ... per item and a single `capacity` constraint.
+	pub fn model(&self) -> Model {
+		let mut model = Model::new().with_options(self.options.clone());
+		let vars = (0..self.values.len())
+			.map(|i| model.add_variable(&format!("x{}", i + 1), 0.0, 1.0, true))
+			.collect::<Vec<_>>();
+
+		let weights = vars.iter().copied().zip(self.weights.iter().copied());
+		model.add_constraint(
+			"capacity",
+			&weights.collect::<Vec<_>>(),
+			Relation::LessEqual,
+			self.capacity,
+		);
+
+		let values = vars.iter().copied().zip(self.values.iter().copied());
+		model.set_objective(Sense::Maximise, &values.collect::<Vec<_>>());
+		model
+	}
+
+	/// Standard form tableau, with the capacity constraint as its only row and its slack column
+	/// after the items. The `x <= 1` bounds are kept in [`Tableau::bounds`].
+	pub fn tableau(&self) -> Tableau {
+		self.model().tableau()
+	}
+
+	/// Solves the problem with the configured [`KnapsackMethod`].
+	pub fn solve(&self) -> Solution {
+		self.solve_with_observer(&mut ())
+	}
+
+	/// Like [`Knapsack::solve`], additionally logging every branch to `writer`.
+	pub fn solve_with_log<W: Write>(&self, writer: &mut W) -> io::Result<Solution> {
+		let mut log = TextLog::new(writer);
+		let solution = self.solve_with_observer(&mut log);
+		log.finish()?;
+		Ok(solution)
+	}
+
+	/// Like [`Knapsack::solve`], reporting progress to `observer`. Dynamic programming only
+	/// reports the final solution.
+	///
+	/// # Panics
+	///
+	/// Panics if a dynamic programming method was picked explicitly and the weights or the capacity
+	/// are not non-negative integers.
+	pub fn solve_with_observer(&self, observer: &mut dyn SolverObserver) -> Solution {
+		let solver: &dyn KnapsackSolver = match self.method {
+			KnapsackMethod::Auto => match self.integer_weights() {
+				Some((weights, capacity)) if capacity <= DP_MAX_CAPACITY => {
+					let table_bits = weights.len().saturating_mul(capacity + 1);
+					if table_bits <= DP_TABLE_BITS {
+						&DynamicProgramming
+					} else {
+						&DynamicProgrammingLean
+					}
+				}
+				_ => &BranchAndBound,
+			},
+			KnapsackMethod::BranchAndBound => &BranchAndBound,
+			KnapsackMethod::DynamicProgramming => &DynamicProgramming,
+			KnapsackMethod::DynamicProgrammingLean => &DynamicProgrammingLean,
+		};
+
+		solver.solve(self, observer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{CancelToken, NodeSelection, SolutionStatus};
+
+	/// The knapsack from the README, whose optimum is 15 and whose relaxation is 15.4.
+	fn readme_knapsack(options: SolverOptions) -> Knapsack {
+		Knapsack::new(
+			vec![2.0, 3.0, 3.0, 5.0, 2.0, 4.0],
+			vec![11.0, 8.0, 6.0, 14.0, 10.0, 10.0],
+			40.0,
+		)
+		.with_method(KnapsackMethod::BranchAndBound)
+		.with_options(options)
+	}
+
+	#[test]
+	fn node_limit_keeps_a_valid_bound() {
+		for threads in [1, 4] {
+			let solution = readme_knapsack(SolverOptions {
+				node_limit: Some(1),
+				threads,
+				..Default::default()
+			})
+			.solve();
+			assert_eq!(solution.status, SolutionStatus::LimitReached);
+			// the root's children are solved but nothing integer turned up yet
+			assert_eq!(solution.objective, f32::NEG_INFINITY);
+			assert!(solution.bound >= solution.objective);
+			assert!(solution.bound >= 15.0 && solution.bound <= 15.4);
+			assert_eq!(solution.gap(), f32::INFINITY);
+		}
+
+		// diving finds 14 on its third node, short of the optimum
+		let solution = readme_knapsack(SolverOptions {
+			node_limit: Some(3),
+			node_selection: NodeSelection::DepthFirst,
+			..Default::default()
+		})
+		.solve();
+		assert_eq!(solution.status, SolutionStatus::LimitReached);
+		assert_eq!(solution.objective, 14.0);
+		assert!(solution.bound >= 15.0 && solution.bound <= 15.4);
+		assert_eq!(solution.gap(), solution.bound - 14.0);
+	}
+
+	#[test]
+	fn cancelled_before_solving() {
+		let cancel = CancelToken::new();
+		cancel.cancel();
+		let solution = readme_knapsack(SolverOptions {
+			cancel,
+			..Default::default()
+		})
+		.solve();
+		assert_eq!(solution.status, SolutionStatus::Cancelled);
+		assert!(solution.values.is_empty());
+		assert!(solution.bound >= solution.objective);
+		assert_eq!(solution.bound, f32::INFINITY);
+		assert_eq!(solution.gap(), f32::INFINITY);
+	}
+
+	#[test]
+	fn pivot_limit_stops_the_root() {
+		let solution = readme_knapsack(SolverOptions {
+			pivot_limit: 1,
+			..Default::default()
+		})
+		.solve();
+		assert_eq!(solution.status, SolutionStatus::LimitReached);
+		assert!(solution.values.is_empty());
+		assert!(solution.bound >= solution.objective);
+		assert_eq!(solution.bound, f32::INFINITY);
+		assert_eq!(solution.gap(), f32::INFINITY);
+	}
+
+	#[test]
+	fn log_reports_the_root_inside_its_block() {
+		let mut log = Vec::new();
+		readme_knapsack(SolverOptions::default())
+			.solve_with_log(&mut log)
+			.unwrap();
+		let log = String::from_utf8(log).unwrap();
+		let lines = log.lines().take(3).collect::<Vec<_>>();
+		assert_eq!(lines[0], "=== Processing Problem 0 ===");
+		assert_eq!(
+			lines[1],
+			"Problem 0: Found optimal solution with objective value: 15.400"
+		);
+		assert!(lines[2].starts_with("Problem 0: Variable values: "));
+	}
+}
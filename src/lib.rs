@@ -1,39 +1,227 @@
-use core::f32;
 use matrix::format::{Compressed, Conventional};
 use std::{
-	collections::VecDeque,
-	fs::File,
-	io::{BufWriter, Write},
+	cmp::Ordering,
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::Instant,
 };
 
-// caution do not continue further, this is a mess and was written in a panic
-// this includes primal/dual simplex and branch and bound with some hacky attempts
-// at keeping track of variables throughout iterations
+mod bounds;
+mod branching;
+mod cancel;
+mod dot;
+mod dynamic;
+mod error;
+mod knapsack;
+mod model;
+mod number;
+mod observer;
+mod options;
+mod parallel;
+mod rational;
+mod selection;
+mod sensitivity;
+mod solution;
+mod solver;
+mod tableau;
+mod trace;
+mod two_phase;
+
+pub use bounds::{Bounds, complement_column, get_basis, get_bounded_values, set_column_bounds};
+pub use branching::{
+	BranchRule, Branching, Candidate, ChildBounds, FirstFractional, MostFractional, Pseudocost,
+	Ratio, SmallestFraction, Strong,
+};
+pub use cancel::CancelToken;
+pub use dot::DotTree;
+pub use dynamic::{knapsack_dp, knapsack_dp_lean};
+pub use error::SolveError;
+pub use knapsack::{Knapsack, KnapsackMethod};
+pub use model::{Model, Relation, Sense, Var};
+pub use number::Number;
+pub use observer::{BranchDirection, SolverEvent, SolverObserver, TextLog};
+pub use options::{PivotRule, SolverOptions, Tolerances};
+pub use rational::Rational;
+pub use selection::{
+	BestBound, BestEstimate, BreadthFirst, DepthFirst, Hybrid, Node, NodeSelection, NodeSelector,
+};
+pub use sensitivity::{
+	ConstraintSensitivity, Sensitivity, VariableSensitivity, get_objective_range, get_reduced_costs,
+	get_rhs_range,
+};
+pub use solution::{PruneReason, PrunedNode, Solution, SolutionStatus};
+pub use solver::{
+	BranchAndBound, DynamicProgramming, DynamicProgrammingLean, Greedy, KnapsackSolver,
+	SolverRegistry,
+};
+pub use tableau::Tableau;
+pub use trace::JsonTrace;
+pub use two_phase::{get_feasible_basis, get_optimal_two_phase};
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct VariableInfo {
 	pub var_type: String,
 	pub index: usize,
+	/// Name used in the log and in solutions.
+	pub name: String,
+	/// Whether branch and bound has to make the variable integral, only `x` variables can be.
+	pub integer: bool,
+}
+
+impl VariableInfo {
+	/// Variable named after its type and one based index, e.g. `x1` or `s3`. Decision variables
+	/// are integer.
+	pub fn new(var_type: &str, index: usize) -> Self {
+		VariableInfo {
+			var_type: var_type.to_string(),
+			index,
+			name: format!("{}{}", var_type, index + 1),
+			integer: var_type == "x",
+		}
+	}
 }
 
 #[derive(Clone)]
-struct Problem {
-	var_count: i32,
-	con_coef: Compressed<f32>,
-	con_rhs_coef: Compressed<f32>,
-	obj_coef: Compressed<f32>,
-	obj_rhs: Compressed<f32>,
+struct Problem<T: Number> {
+	/// Optimal tableau of the node's relaxation, with the objective row in maximisation form.
+	tableau: Tableau<T>,
+	/// Bounds the branches leading to the problem gave its variables, oldest first.
+	changes: Vec<BoundChange<T>>,
 	section: String,
-	variable_map: Vec<VariableInfo>,
+	options: SolverOptions,
 }
 
-/// Entering variable is determined by the most negative coefficient in the objective function.
-pub fn get_primal_enter_var(obj_coef: &Compressed<f32>) -> i32 {
-	let mut min_value = f32::INFINITY;
+impl<T: Number> Problem<T> {
+	/// Objective value in maximisation form, which is what every comparison uses.
+	fn objective(&self) -> T {
+		self.tableau.obj_rhs.get((0, 0))
+	}
+
+	/// Objective value in the problem's own sense, for reporting.
+	fn reported_objective(&self) -> T {
+		self.tableau.sense.signum::<T>() * self.objective()
+	}
+
+	/// Values of the variables behind every column in the current tableau.
+	fn values(&self) -> Vec<T> {
+		let tableau = &self.tableau;
+		get_bounded_values(&tableau.con_rhs_coef, &tableau.bounds, &tableau.basis)
+	}
+
+	/// Values of the `x` variables in the current tableau.
+	fn x_values(&self) -> Vec<T> {
+		self
+			.values()
+			.into_iter()
+			.zip(&self.tableau.variable_map)
+			.filter(|(_, v)| v.var_type == "x")
+			.map(|(value, _)| value)
+			.collect()
+	}
+
+	fn x_names(&self) -> Vec<String> {
+		self
+			.tableau
+			.variable_map
+			.iter()
+			.filter(|v| v.var_type == "x")
+			.map(|v| v.name.clone())
+			.collect()
+	}
+}
+
+/// Bounds a branch gave a column.
+#[derive(Clone, Copy, Debug)]
+struct BoundChange<T> {
+	column: usize,
+	lower: T,
+	upper: T,
+}
+
+/// What an open node keeps of its optimal tableau, which is rebuilt from the root's when the node is
+/// processed. Only the bound changes and the final basis are kept, so a node costs a couple of
+/// vectors rather than a whole tableau.
+struct Snapshot<T> {
+	/// Objective value of the node's relaxation, exact unlike [`Node::bound`].
+	bound: T,
+	changes: Vec<BoundChange<T>>,
+	/// Basic columns, in no particular row order.
+	basis: Vec<usize>,
+	flipped: Vec<bool>,
+}
+
+impl<T: Number> Snapshot<T> {
+	fn of(problem: &Problem<T>) -> Self {
+		Snapshot {
+			bound: problem.objective(),
+			changes: problem.changes.clone(),
+			basis: problem.tableau.basis.iter().flatten().copied().collect(),
+			flipped: problem.tableau.bounds.flipped.clone(),
+		}
+	}
+
+	/// Rebuilds the node's tableau by applying its bound changes to the root's tableau and pivoting
+	/// the node's basis in. The dual simplex then cleans up whatever rounding left behind.
+	fn rebuild(self, root: &Problem<T>, section: String) -> Result<Problem<T>, SolveError> {
+		let mut problem = root.clone();
+		let tableau = &mut problem.tableau;
+		for change in &self.changes {
+			set_column_bounds(tableau, change.column, change.lower, change.upper);
+		}
+
+		let mut iterations = 0;
+		for &column in &self.basis {
+			if tableau.basis.contains(&Some(column)) {
+				continue;
+			}
+
+			// any row whose basic column leaves can take the column, the largest coefficient is
+			// the most stable pivot
+			let coef = |i: usize| tableau.con_coef.get((i, column)).abs();
+			let row = (0..tableau.con_coef.rows)
+				.filter(|&i| tableau.basis[i].is_none_or(|j| !self.basis.contains(&j)))
+				.max_by(|&a, &b| coef(a).partial_cmp(&coef(b)).unwrap_or(Ordering::Equal))
+				.expect("a basis has no more columns than there are rows");
+
+			checked_pivot(
+				tableau,
+				column as i32,
+				row as i32,
+				&mut iterations,
+				&root.options,
+			)?;
+		}
+
+		for (column, &flipped) in self.flipped.iter().enumerate() {
+			if tableau.bounds.flipped[column] != flipped {
+				complement_column(tableau, column);
+			}
+		}
+
+		get_optimal_dual(tableau, &root.options, &mut ())?;
+
+		problem.section = section;
+		problem.changes = self.changes;
+		Ok(problem)
+	}
+}
+
+/// Entering variable is determined by the most negative coefficient in the objective function, or
+/// by the first negative one under [`PivotRule::Bland`].
+pub fn get_primal_enter_var<T: Number>(
+	obj_coef: &Compressed<T>,
+	rule: PivotRule,
+	tolerances: &Tolerances,
+) -> i32 {
+	let mut min_value = -T::tolerance(tolerances.optimality);
 	let mut min_index = -1;
 
 	for (i, &value) in obj_coef.values.iter().enumerate() {
-		if value < min_value && value < 0.0 {
+		if value < min_value {
+			if rule == PivotRule::Bland {
+				return i as i32;
+			}
+
 			min_value = value;
 			min_index = i as i32;
 		}
@@ -42,46 +230,136 @@ pub fn get_primal_enter_var(obj_coef: &Compressed<f32>) -> i32 {
 	min_index
 }
 
-/// Leaving variable is determined by the minimum ratio of the right-hand side to the entering variable's coefficient.
-pub fn get_primal_leaving_var(
-	con_coef: &Compressed<f32>,
-	rhs_coef: &Compressed<f32>,
+/// Outcome of the primal ratio test.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Leaving {
+	/// The basic variable of the row drops to its lower bound.
+	Lower(usize),
+	/// The basic variable of the row rises to its upper bound.
+	Upper(usize),
+	/// The entering variable reaches its own upper bound first, so nothing leaves the basis.
+	Flip,
+	/// Nothing limits how far the entering variable can grow.
+	Unbounded,
+}
+
+/// Leaving variable is determined by the minimum ratio of how far each basic variable is from the
+/// bound it moves towards to the entering variable's coefficient. `basis` holds the basic column of
+/// every row, ties between rows are broken as `rule` describes.
+pub fn get_primal_leaving_var<T: Number>(
+	con_coef: &Compressed<T>,
+	rhs_coef: &Compressed<T>,
 	enter_idx: i32,
-) -> i32 {
-	let mut min_ratio = f32::INFINITY;
-	let mut min_index = -1;
+	bounds: &Bounds<T>,
+	basis: &[Option<usize>],
+	rule: PivotRule,
+	tolerances: &Tolerances,
+) -> Leaving {
+	let zero = T::tolerance(tolerances.pivot);
+	let column = enter_idx as usize;
+	let ratio = |i: usize| {
+		let rhs = rhs_coef.get((i, 0));
+		let row_coef = con_coef.get((i, column));
+		if row_coef > zero {
+			// bound flips routinely leave right-hand sides at exactly zero, such rows block the
+			// entering variable as much as any other
+			Some((rhs.max(T::zero()) / row_coef, Leaving::Lower(i)))
+		} else if row_coef < -zero {
+			// the basic variable grows along with the entering one, which only matters when it
+			// has an upper bound
+			let range = bounds.range(basis[i]?);
+			Some(((range - rhs).max(T::zero()) / -row_coef, Leaving::Upper(i)))
+		} else {
+			None
+		}
+	};
 
-	for (i, &rhs) in rhs_coef.values.iter().enumerate() {
-		if rhs > 0.0 {
-			let row_coef = con_coef.get((i, enter_idx as usize));
-			let ratio = rhs / row_coef;
-			if ratio < min_ratio {
-				if ratio == 0.0 && row_coef != 1.0 {
-					continue;
-				}
-
-				min_ratio = ratio;
-				min_index = i as i32;
-			}
+	let mut min_ratio = bounds.range(column);
+	let mut leaving = if min_ratio.is_finite() {
+		Leaving::Flip
+	} else {
+		Leaving::Unbounded
+	};
+
+	for i in 0..con_coef.rows {
+		if let Some((ratio, row)) = ratio(i)
+			&& ratio < min_ratio
+		{
+			min_ratio = ratio;
+			leaving = row;
 		}
 	}
 
-	min_index
+	if rule == PivotRule::Dantzig || matches!(leaving, Leaving::Flip | Leaving::Unbounded) {
+		return leaving;
+	}
+
+	let tied = (0..con_coef.rows)
+		.filter_map(ratio)
+		.filter(|&(ratio, _)| ratio <= min_ratio + zero)
+		.map(|(_, row)| row);
+	let row_of = |leaving: &Leaving| match *leaving {
+		Leaving::Lower(i) | Leaving::Upper(i) => i,
+		Leaving::Flip | Leaving::Unbounded => unreachable!("only rows tie"),
+	};
+
+	match rule {
+		PivotRule::Bland => tied.min_by_key(|row| basis[row_of(row)].unwrap_or(usize::MAX)),
+		_ => tied.min_by(|a, b| {
+			lexicographic_row(con_coef, basis, column, *a)
+				.zip(lexicographic_row(con_coef, basis, column, *b))
+				.map(|(a, b)| a.partial_cmp(&b).unwrap_or(Ordering::Equal))
+				.find(|&ordering| ordering != Ordering::Equal)
+				.unwrap_or(Ordering::Equal)
+		}),
+	}
+	.unwrap_or(leaving)
+}
+
+/// Row of the leaving variable as it will be once the variable has left, divided by the entering
+/// column's coefficient. Rows whose variable leaves at its upper bound are complemented first,
+/// which only changes the sign of the basic column's entry.
+fn lexicographic_row<T: Number>(
+	con_coef: &Compressed<T>,
+	basis: &[Option<usize>],
+	column: usize,
+	leaving: Leaving,
+) -> impl Iterator<Item = T> {
+	let (row, upper) = match leaving {
+		Leaving::Lower(i) => (i, false),
+		Leaving::Upper(i) => (i, true),
+		Leaving::Flip | Leaving::Unbounded => unreachable!("only rows are compared"),
+	};
+
+	let coef = con_coef.get((row, column));
+	(0..con_coef.columns).map(move |j| {
+		let value = con_coef.get((row, j)) / coef;
+		if upper && basis[row] == Some(j) {
+			-value
+		} else {
+			value
+		}
+	})
 }
 
 /// Dual entering variable is determined by the lowest ratio of the objective function coefficient to the constraint coefficient.
-pub fn get_dual_enter_var(obj_coef: &Compressed<f32>, leave_coef: Vec<f32>) -> i32 {
-	let mut min_ratio = f32::INFINITY;
+pub fn get_dual_enter_var<T: Number>(
+	obj_coef: &Compressed<T>,
+	leave_coef: Vec<T>,
+	tolerances: &Tolerances,
+) -> i32 {
+	let mut min_ratio = T::infinity();
 	let mut min_index = -1;
 
 	for (i, &value) in obj_coef.values.iter().enumerate() {
 		let leaving_value = leave_coef[i];
-		if leaving_value >= 0.0 {
+		if leaving_value >= -T::tolerance(tolerances.pivot) {
 			continue;
 		}
 
+		// zero ratios are allowed, degenerate columns are as good a way out of an infeasible row
 		let ratio = (value / leaving_value).abs();
-		if ratio > 0.0 && ratio < min_ratio {
+		if ratio < min_ratio {
 			min_ratio = ratio;
 			min_index = i as i32;
 		}
@@ -90,26 +368,43 @@ pub fn get_dual_enter_var(obj_coef: &Compressed<f32>, leave_coef: Vec<f32>) -> i
 	min_index
 }
 
-/// Dual leaving variable is determined by the most negative value in the right-hand side coefficients.
-pub fn get_dual_leaving_var(con_rhs_coef: &Compressed<f32>) -> i32 {
-	let mut min_value = f32::INFINITY;
-	let mut min_index = -1;
+/// Dual leaving variable is determined by the basic variable furthest outside its bounds, either
+/// below zero or above the range of its column, see [`Bounds::range`]. Under [`PivotRule::Bland`]
+/// it is the lowest basic column outside its bounds instead.
+pub fn get_dual_leaving_var<T: Number>(
+	con_rhs_coef: &Compressed<T>,
+	ranges: &[T],
+	basis: &[Option<usize>],
+	rule: PivotRule,
+	tolerances: &Tolerances,
+) -> i32 {
+	let feasibility = T::tolerance(tolerances.feasibility);
+	let mut max_violation = feasibility;
+	let mut max_index = -1;
 
 	for (i, &value) in con_rhs_coef.values.iter().enumerate() {
-		if value < 0.0 && value < min_value {
-			min_value = value;
-			min_index = i as i32;
+		let violation = (-value).max(value - ranges[i]);
+		if rule == PivotRule::Bland {
+			let lower = (max_index >= 0).then(|| basis[max_index as usize]);
+			if violation > feasibility && lower.is_none_or(|lower| basis[i] < lower) {
+				max_index = i as i32;
+			}
+		} else if violation > max_violation {
+			max_violation = violation;
+			max_index = i as i32;
 		}
 	}
 
-	min_index
+	max_index
 }
 
-pub fn pivot_coef(
-	con_coef: &mut Compressed<f32>,
-	con_rhs_coef: &mut Compressed<f32>,
-	obj_coef: &mut Compressed<f32>,
-	rhs_coef: &mut Compressed<f32>,
+/// Pivots the entering column into the leaving row, which it replaces in `basis`.
+pub fn pivot_coef<T: Number>(
+	con_coef: &mut Compressed<T>,
+	con_rhs_coef: &mut Compressed<T>,
+	obj_coef: &mut Compressed<T>,
+	rhs_coef: &mut Compressed<T>,
+	basis: &mut [Option<usize>],
 	enter_idx: i32,
 	leaving_idx: i32,
 ) {
@@ -122,14 +417,13 @@ pub fn pivot_coef(
 		.collect::<Vec<_>>();
 	let pivot_value = pivot_row[enter_idx as usize];
 
-	for j in 0..con_coef.columns {
+	for (j, &value) in pivot_row.iter().enumerate() {
 		if j != enter_idx as usize {
-			let new_value = pivot_row[j] / pivot_value;
-			con_coef.set((leaving_idx as usize, j), new_value);
+			con_coef.set((leaving_idx as usize, j), value / pivot_value);
 		}
 	}
 
-	con_coef.set((leaving_idx as usize, enter_idx as usize), 1.0);
+	con_coef.set((leaving_idx as usize, enter_idx as usize), T::one());
 	// set corresponding rhs value in pivot row
 	con_rhs_coef.set((leaving_idx as usize, 0), rhs_value / pivot_value);
 
@@ -155,554 +449,784 @@ pub fn pivot_coef(
 	}
 
 	let pivot_rhs_val = con_rhs_coef.get((leaving_idx as usize, 0));
-	rhs_coef.set((0, 0), rhs_coef.get((0, 0)) - obj_factor * pivot_rhs_val)
+	rhs_coef.set((0, 0), rhs_coef.get((0, 0)) - obj_factor * pivot_rhs_val);
+	basis[leaving_idx as usize] = Some(enter_idx as usize);
+}
+
+/// Bases a simplex run has visited since its objective last moved. Degenerate pivots leave the
+/// objective where it is, so a basis coming back means the run is cycling, at which point the
+/// guard switches it to [`PivotRule::Bland`].
+struct CycleGuard<T> {
+	rule: PivotRule,
+	objective: T,
+	/// Basic column of every row along with the flipped columns, since a bound flip changes the
+	/// vertex without changing the basis.
+	visited: HashSet<(Vec<Option<usize>>, Vec<bool>)>,
 }
 
-pub fn get_optimal_primal(
-	con_coef: &mut Compressed<f32>,
-	con_rhs_coef: &mut Compressed<f32>,
-	obj_coef: &mut Compressed<f32>,
-	rhs_coef: &mut Compressed<f32>,
-) -> Result<(), ()> {
+impl<T: Number> CycleGuard<T> {
+	fn new(rule: PivotRule, objective: T) -> Self {
+		CycleGuard {
+			rule,
+			objective,
+			visited: HashSet::new(),
+		}
+	}
+
+	/// Records the basis the run is at, returning the rule to pick the next pivot by.
+	fn visit(
+		&mut self,
+		basis: &[Option<usize>],
+		bounds: &Bounds<T>,
+		objective: T,
+		tolerances: &Tolerances,
+	) -> PivotRule {
+		if self.rule == PivotRule::Bland {
+			return self.rule;
+		}
+
+		if (objective - self.objective).abs() > T::tolerance(tolerances.optimality) {
+			self.objective = objective;
+			self.visited.clear();
+		}
+
+		if !self
+			.visited
+			.insert((basis.to_vec(), bounds.flipped.clone()))
+		{
+			self.rule = PivotRule::Bland;
+		}
+
+		self.rule
+	}
+}
+
+/// Runs the primal simplex until optimal, returning the number of pivots performed. The objective
+/// row has to be in maximisation form, minimisation rows can be negated first, see [`Sense::signum`].
+/// The tableau's basis is kept up to date.
+pub fn get_optimal_primal<T: Number>(
+	tableau: &mut Tableau<T>,
+	options: &SolverOptions,
+) -> Result<usize, SolveError> {
+	continue_primal(tableau, options, 0, &mut ())
+}
+
+/// Primal simplex picking up after `iterations` earlier pivots, reporting every pivot to
+/// `observer`. Moving a non-basic variable to its other bound counts towards the iterations but
+/// isn't a pivot.
+fn continue_primal<T: Number>(
+	tableau: &mut Tableau<T>,
+	options: &SolverOptions,
+	mut iterations: usize,
+	observer: &mut dyn SolverObserver<T>,
+) -> Result<usize, SolveError> {
+	let tolerances = &options.tolerances;
+	let mut guard = CycleGuard::new(options.pivot_rule, tableau.obj_rhs.get((0, 0)));
 	loop {
-		// maximisation checks if all coefficients in the objective function are non-negative
-		if obj_coef.values.iter().all(|&x| x >= 0.0) {
+		let rule = guard.visit(
+			&tableau.basis,
+			&tableau.bounds,
+			tableau.obj_rhs.get((0, 0)),
+			tolerances,
+		);
+
+		// in maximisation form the tableau is optimal once no objective coefficient is negative
+		let enter_idx = get_primal_enter_var(&tableau.obj_coef, rule, tolerances);
+		if enter_idx == -1 {
 			break;
 		}
 
-		let enter_idx = get_primal_enter_var(&obj_coef);
-		let leaving_idx = get_primal_leaving_var(&con_coef, &con_rhs_coef, enter_idx);
-		if leaving_idx == -1 {
-			return Err(());
-		}
+		let leaving_idx = match get_primal_leaving_var(
+			&tableau.con_coef,
+			&tableau.con_rhs_coef,
+			enter_idx,
+			&tableau.bounds,
+			&tableau.basis,
+			rule,
+			tolerances,
+		) {
+			Leaving::Lower(row) => row as i32,
+			Leaving::Upper(row) => {
+				// measured from its upper bound the leaving variable drops to zero like any other
+				let basic = tableau.basis[row].expect("only rows with a basic column can leave");
+				complement_column(tableau, basic);
+				row as i32
+			}
+			Leaving::Flip => {
+				check_interrupted(iterations, options)?;
+				complement_column(tableau, enter_idx as usize);
+				iterations += 1;
+				continue;
+			}
+			Leaving::Unbounded => {
+				return Err(SolveError::Unbounded {
+					column: enter_idx as usize,
+					iterations,
+				});
+			}
+		};
 
 		// pivot the tableau
-		pivot_coef(
-			con_coef,
-			con_rhs_coef,
-			obj_coef,
-			rhs_coef,
-			enter_idx,
-			leaving_idx,
-		);
+		checked_pivot(tableau, enter_idx, leaving_idx, &mut iterations, options)?;
+		observer.on_event(&SolverEvent::PivotPerformed {
+			column: enter_idx as usize,
+			row: leaving_idx as usize,
+			variable: &tableau.variable_map[enter_idx as usize].name,
+			iterations,
+			dual: false,
+		});
 	}
 
-	Ok(())
+	Ok(iterations)
 }
 
-pub fn get_optimal_dual(
-	con_coef: &mut Compressed<f32>,
-	con_rhs_coef: &mut Compressed<f32>,
-	obj_coef: &mut Compressed<f32>,
-	rhs_coef: &mut Compressed<f32>,
-	writer: &mut BufWriter<File>,
-	var_map: &Vec<VariableInfo>,
-) -> Result<(), ()> {
-	let mut initial_pivot = false;
+/// Runs the dual simplex until the right-hand side is feasible and then finishes with the primal
+/// simplex, returning the total number of pivots performed. Every pivot is reported to `observer`.
+pub fn get_optimal_dual<T: Number>(
+	tableau: &mut Tableau<T>,
+	options: &SolverOptions,
+	observer: &mut dyn SolverObserver<T>,
+) -> Result<usize, SolveError> {
+	let tolerances = &options.tolerances;
+	let mut guard = CycleGuard::new(options.pivot_rule, tableau.obj_rhs.get((0, 0)));
+	let mut iterations = 0;
 
 	loop {
-		if con_rhs_coef.values.iter().all(|&v| v > -1e-6) {
+		let rule = guard.visit(
+			&tableau.basis,
+			&tableau.bounds,
+			tableau.obj_rhs.get((0, 0)),
+			tolerances,
+		);
+		let ranges = tableau
+			.basis
+			.iter()
+			.map(|basic| basic.map_or(T::infinity(), |j| tableau.bounds.range(j)))
+			.collect::<Vec<_>>();
+
+		let leaving_idx = get_dual_leaving_var(
+			&tableau.con_rhs_coef,
+			&ranges,
+			&tableau.basis,
+			rule,
+			tolerances,
+		);
+		if leaving_idx == -1 {
 			break;
 		}
 
-		let leaving_idx = get_dual_leaving_var(&con_rhs_coef);
-		let filled_con_coef = Conventional::from(con_coef.clone());
+		let row = leaving_idx as usize;
+		if tableau.con_rhs_coef.get((row, 0)) > ranges[row] {
+			// above its upper bound, measured from that bound the basic variable is negative instead
+			let basic = tableau.basis[row].expect("rows above their range have a basic column");
+			complement_column(tableau, basic);
+		}
+
+		let filled_con_coef = Conventional::from(tableau.con_coef.clone());
 		let leave_coef = (0..filled_con_coef.columns)
 			.map(|j| filled_con_coef[(leaving_idx as usize, j)])
 			.collect::<Vec<_>>();
 
-		let enter_idx = get_dual_enter_var(&obj_coef, leave_coef);
+		let enter_idx = get_dual_enter_var(&tableau.obj_coef, leave_coef, tolerances);
 		if enter_idx == -1 {
-			if initial_pivot == false {
-				writeln!(
-					writer,
-					"<> Infeasible dual problem, no entering variable found. Attempted with leaving row {}",
-					leaving_idx + 1
-				)
-				.ok();
-			}
-
-			return Err(());
-		}
-
-		if initial_pivot == false {
-			writeln!(
-				writer,
-				"<> Initial pivoting for dual problem with entering variable {}{} and leaving row {}",
-				var_map[enter_idx as usize].var_type,
-				var_map[enter_idx as usize].index + 1,
-				leaving_idx + 1
-			)
-			.ok();
+			return Err(SolveError::Infeasible {
+				row: leaving_idx as usize,
+				iterations,
+			});
 		}
 
-		initial_pivot = true;
-
 		// pivot the tableau
-		pivot_coef(
-			con_coef,
-			con_rhs_coef,
-			obj_coef,
-			rhs_coef,
-			enter_idx,
-			leaving_idx,
-		);
+		checked_pivot(tableau, enter_idx, leaving_idx, &mut iterations, options)?;
+		observer.on_event(&SolverEvent::PivotPerformed {
+			column: enter_idx as usize,
+			row: leaving_idx as usize,
+			variable: &tableau.variable_map[enter_idx as usize].name,
+			iterations,
+			dual: true,
+		});
 	}
 
-	let primal_result = get_optimal_primal(con_coef, con_rhs_coef, obj_coef, rhs_coef);
-	primal_result
+	continue_primal(tableau, options, iterations, observer)
 }
 
-pub fn get_branch_var(
-	var_count: i32,
-	con_coef: &Compressed<f32>,
-	con_rhs_coef: &Compressed<f32>,
-) -> (i32, f32) {
-	let mut min_value = f32::INFINITY;
-	let mut min_index = -1;
-
-	let basic_cols = (0..var_count as usize)
-		.filter(|j| {
-			let mut found_one = false;
-			for i in 0..con_coef.rows {
-				let coef = con_coef.get((i, *j));
-				if coef == 1.0 {
-					if found_one {
-						return false;
-					}
-
-					found_one = true;
-				} else if coef != 0.0 {
-					return false;
-				}
-			}
+/// Stops a simplex run that reached the pivot limit or was cancelled.
+fn check_interrupted(iterations: usize, options: &SolverOptions) -> Result<(), SolveError> {
+	if options.cancel.is_cancelled() {
+		Err(SolveError::Cancelled { iterations })
+	} else if iterations >= options.pivot_limit {
+		Err(SolveError::IterationLimit { iterations })
+	} else {
+		Ok(())
+	}
+}
 
-			found_one
-		})
-		.collect::<Vec<_>>();
+/// Guards [`pivot_coef`] against tiny pivot elements, runaway iteration counts, cancellation and
+/// non-finite results.
+fn checked_pivot<T: Number>(
+	tableau: &mut Tableau<T>,
+	enter_idx: i32,
+	leaving_idx: i32,
+	iterations: &mut usize,
+	options: &SolverOptions,
+) -> Result<(), SolveError> {
+	check_interrupted(*iterations, options)?;
+
+	let numerical = SolveError::Numerical {
+		row: leaving_idx as usize,
+		column: enter_idx as usize,
+		iterations: *iterations,
+	};
 
-	for (i, &value) in con_rhs_coef.values.iter().enumerate() {
-		if value.fract() == 0.0 {
-			// ignore integer solutions
-			continue;
-		}
+	let pivot_value = tableau
+		.con_coef
+		.get((leaving_idx as usize, enter_idx as usize));
+	if !pivot_value.is_finite() || pivot_value.abs() <= T::tolerance(options.tolerances.pivot) {
+		return Err(numerical);
+	}
 
-		for &j in &basic_cols {
-			let coef = con_coef.get((i, j));
-			if coef == 0.0 || coef != 1.0 {
-				continue;
-			}
+	pivot_coef(
+		&mut tableau.con_coef,
+		&mut tableau.con_rhs_coef,
+		&mut tableau.obj_coef,
+		&mut tableau.obj_rhs,
+		&mut tableau.basis,
+		enter_idx,
+		leaving_idx,
+	);
 
-			let frac_diff = value - value.floor();
-			if frac_diff < min_value {
-				min_value = frac_diff;
-				min_index = j as i32;
-				break;
-			}
-		}
+	*iterations += 1;
+	if !tableau.obj_rhs.get((0, 0)).is_finite() {
+		return Err(numerical);
 	}
 
-	(min_index, min_value)
+	Ok(())
+}
+
+/// Whether the value is within the integrality tolerance of an integer.
+pub fn is_integral<T: Number>(value: T, tolerances: &Tolerances) -> bool {
+	(value - value.round()).abs() <= T::tolerance(tolerances.integrality)
 }
 
-pub fn branch_and_bound(
+/// Basic `x` variables whose value is fractional, ordered by column.
+pub fn get_branch_candidates<T: Number>(
 	var_count: i32,
-	con_coef: &Compressed<f32>,
-	con_rhs_coef: &Compressed<f32>,
-	obj_coef: &Compressed<f32>,
-	obj_rhs: &Compressed<f32>,
-	initial_variable_map: Vec<VariableInfo>,
-) -> std::io::Result<()> {
-	let file = File::create("branches.txt")?;
-	let mut writer = BufWriter::new(file);
-
-	let mut queue = VecDeque::new();
-	let root_problem = Problem {
-		var_count,
-		con_coef: con_coef.clone(),
-		con_rhs_coef: con_rhs_coef.clone(),
-		obj_coef: obj_coef.clone(),
-		obj_rhs: obj_rhs.clone(),
+	con_rhs_coef: &Compressed<T>,
+	bounds: &Bounds<T>,
+	basis: &[Option<usize>],
+	tolerances: &Tolerances,
+) -> Vec<Candidate<T>> {
+	(0..var_count as usize)
+		.filter_map(|j| {
+			let basic_row = basis.iter().position(|&basic| basic == Some(j))?;
+			let value = bounds.value(j, con_rhs_coef.get((basic_row, 0)));
+			// ignore integer solutions
+			(!is_integral(value, tolerances)).then_some(Candidate { var: j, value })
+		})
+		.collect()
+}
+
+/// Branch and bound over the optimal tableau of the root relaxation. The objective row has to be in
+/// maximisation form, the tableau's `sense` only decides how objective values are reported to
+/// `observer` and in the returned solution.
+///
+/// Node selectors and branching rules only see `f32` approximations of the values, everything else
+/// is computed in `T`.
+///
+/// The search stops early at the node and time limits in `options`, and can't rule out subtrees
+/// whose relaxation hit the pivot limit. Either way the solution has the status
+/// [`SolutionStatus::LimitReached`] and its `bound` covers the nodes left unexplored. Cancelling
+/// `options.cancel` stops it the same way with [`SolutionStatus::Cancelled`].
+/// A relaxation failing for another reason, such as numerical trouble, leaves its subtree
+/// unexplored too, which gives [`SolutionStatus::Failed`] unless the search also stopped early.
+///
+/// With more than one of `options.threads` the relaxations are solved on worker threads, while
+/// selectors, branching rules and `observer` stay on the calling thread.
+///
+/// Pass `&mut ()` as the observer to ignore progress, or a [`TextLog`] for a readable log.
+pub fn branch_and_bound<T: Number>(
+	root: Tableau<T>,
+	options: &SolverOptions,
+	observer: &mut dyn SolverObserver<T>,
+) -> Solution<T> {
+	let root = Problem {
+		tableau: root,
+		changes: Vec::new(),
 		section: "0".to_string(),
-		variable_map: initial_variable_map,
+		options: options.clone(),
 	};
 
-	queue.push_back(root_problem);
-	while let Some(current_problem) = queue.pop_front() {
-		writeln!(
-			writer,
-			"=== Processing Problem {} ===",
-			current_problem.section
-		)?;
-
-		let int_obj_vars = current_problem
-			.con_rhs_coef
-			.values
-			.iter()
-			.all(|&v| v.fract() == 0.0);
-		if int_obj_vars {
-			writeln!(
-				writer,
-				"Problem {}: All variables are integers, optimal solution found.",
-				current_problem.section
-			)?;
-
-			writeln!(
-				writer,
-				"Problem {}: Objective value: {}\n",
-				current_problem.section,
-				current_problem.obj_rhs.get((0, 0))
-			)?;
-			continue;
-		}
+	let mut search = Search::new(root, options, observer);
+	if options.threads > 1 {
+		parallel::search(&mut search);
+	} else {
+		while let Some((section, snapshot)) = search.next() {
+			let bound = snapshot.bound;
+			let rebuilt = snapshot.rebuild(&search.root, section.clone());
+			let Some(mut branch) = search.branch(&section, bound, rebuilt) else {
+				continue;
+			};
 
-		let (branch_var_idx, branch_var_value) = get_branch_var(
-			current_problem
-				.variable_map
-				.iter()
-				.filter(|v| v.var_type == "x")
-				.count() as i32,
-			&current_problem.con_coef,
-			&current_problem.con_rhs_coef,
-		);
+			for direction in [BranchDirection::Down, BranchDirection::Up] {
+				let section = search.announce(&branch, direction);
+				let child = create_branch(
+					&branch.problem,
+					&branch.candidate,
+					direction,
+					&mut *search.observer,
+				);
+				search.settle(&mut branch, direction, &section, child);
+			}
 
-		if branch_var_idx == -1 {
-			writeln!(
-				writer,
-				"Problem {}: No branching variable found, optimal solution reached.",
-				current_problem.section
-			)?;
-			writeln!(
-				writer,
-				"Problem {}: Final objective value: {}\n",
-				current_problem.section,
-				current_problem.obj_rhs.get((0, 0))
-			)?;
-			continue;
+			search.close(branch);
 		}
+	}
 
-		writeln!(
-			writer,
-			"Problem {}: Branching on variable {} with value {}",
-			current_problem.section,
-			branch_var_idx + 1,
-			branch_var_value
-		)?;
-
-		let new_var_count = current_problem.var_count + 1;
+	search.finish()
+}
 
-		writeln!(
-			writer,
-			"--- Creating branch {}.1 ({}{} ≤ {}) ---",
-			current_problem.section,
-			current_problem.variable_map[branch_var_idx as usize].var_type,
-			current_problem.variable_map[branch_var_idx as usize].index + 1,
-			branch_var_value.floor()
-		)?;
+/// Everything a branch and bound run keeps between nodes. [`branch_and_bound`] drives it one node
+/// at a time, [`parallel::search`] with several nodes and children solving at once.
+struct Search<'a, T: Number> {
+	root: Arc<Problem<T>>,
+	options: &'a SolverOptions,
+	observer: &'a mut dyn SolverObserver<T>,
+	queue: Box<dyn NodeSelector>,
+	rule: Box<dyn BranchRule>,
+	/// The queue only orders nodes, what is needed to rebuild them is kept here.
+	snapshots: HashMap<usize, Snapshot<T>>,
+	next_id: usize,
+	best: Solution<T>,
+	pruned: Vec<PrunedNode>,
+	/// Relaxation bounds of subtrees given up on because the simplex failed for another reason than
+	/// infeasibility, such as the pivot limit or a cancellation, along with the failure.
+	abandoned: Vec<(T, SolveError)>,
+	opened: usize,
+	start: Instant,
+}
 
-		let left_problem = create_left_branch(
-			&current_problem,
-			branch_var_idx,
-			branch_var_value,
-			new_var_count,
-			&mut writer,
-		)?;
+/// A node being branched on, whose children are still to be settled.
+struct Branch<T: Number> {
+	problem: Arc<Problem<T>>,
+	candidate: Candidate<T>,
+	/// The candidate as the branching rule saw it.
+	approximate: Candidate,
+	children: ChildBounds,
+}
 
-		if let Some(problem) = left_problem {
-			queue.push_back(problem);
+impl<'a, T: Number> Search<'a, T> {
+	fn new(
+		root: Problem<T>,
+		options: &'a SolverOptions,
+		observer: &'a mut dyn SolverObserver<T>,
+	) -> Self {
+		let mut queue = options.node_selection.build();
+		queue.push(Node::new(0, &root));
+		Search {
+			snapshots: HashMap::from([(0, Snapshot::of(&root))]),
+			root: Arc::new(root),
+			options,
+			observer,
+			queue,
+			rule: options.branching.build(),
+			next_id: 0,
+			best: Solution::infeasible(),
+			pruned: Vec::new(),
+			abandoned: Vec::new(),
+			opened: 0,
+			start: Instant::now(),
 		}
+	}
 
-		writeln!(
-			writer,
-			"--- Creating branch {}.2 ({}{} ≥ {}) ---",
-			current_problem.section,
-			current_problem.variable_map[branch_var_idx as usize].var_type,
-			current_problem.variable_map[branch_var_idx as usize].index + 1,
-			branch_var_value.ceil()
-		)?;
+	/// Takes the next node worth rebuilding off the queue, closing the ones that can't beat the
+	/// incumbent on the way. Returns `None` once the queue is empty or a limit stops the search.
+	fn next(&mut self) -> Option<(String, Snapshot<T>)> {
+		let options = self.options;
+		let signum = self.root.tableau.sense.signum::<T>();
+		while let Some(node) = self.queue.pop() {
+			if options.cancel.is_cancelled()
+				|| options.node_limit.is_some_and(|limit| self.opened >= limit)
+				|| options
+					.time_limit
+					.is_some_and(|limit| self.start.elapsed() >= limit)
+			{
+				// the node keeps its snapshot, so it still counts towards the remaining bound
+				return None;
+			}
 
-		let right_problem = create_right_branch(
-			&current_problem,
-			branch_var_idx,
-			branch_var_value,
-			new_var_count,
-			&mut writer,
-		)?;
+			self.opened += 1;
+			let snapshot = self
+				.snapshots
+				.remove(&node.id())
+				.expect("every queued node has a snapshot");
+
+			self.observer.on_event(&SolverEvent::NodeOpened {
+				section: node.section(),
+				depth: node.depth(),
+				bound: signum * snapshot.bound,
+			});
+
+			// the incumbent may have improved since this node was queued, which spares rebuilding it
+			if !can_improve(&self.best, snapshot.bound, options) {
+				prune(
+					&mut self.pruned,
+					self.observer,
+					node.section(),
+					PruneReason::Bound,
+					Some(signum * snapshot.bound),
+				);
+				continue;
+			}
 
-		if let Some(problem) = right_problem {
-			queue.push_back(problem);
+			return Some((node.section().to_string(), snapshot));
 		}
 
-		writeln!(writer)?;
+		None
 	}
 
-	writeln!(writer, "=== Branch and Bound Algorithm Completed ===")?;
-	writer.flush()?;
+	/// Closes a rebuilt node that failed or is already integral, otherwise picks the variable to
+	/// branch it on. `bound` is the node's relaxation bound from before the rebuild.
+	fn branch(
+		&mut self,
+		section: &str,
+		bound: T,
+		rebuilt: Result<Problem<T>, SolveError>,
+	) -> Option<Branch<T>> {
+		let problem = match rebuilt {
+			Ok(problem) => problem,
+			Err(error) => {
+				self.abandon(section, bound, error);
+				return None;
+			}
+		};
+
+		let tableau = &problem.tableau;
+		let mut candidates = get_branch_candidates(
+			tableau.var_count,
+			&tableau.con_rhs_coef,
+			&tableau.bounds,
+			&tableau.basis,
+			&problem.options.tolerances,
+		);
+		candidates.retain(|c| tableau.variable_map[c.var].integer);
+
+		// nodes without fractional integer variables can't be branched on any further
+		if candidates.is_empty() {
+			prune(
+				&mut self.pruned,
+				self.observer,
+				&problem.section,
+				PruneReason::Integral,
+				Some(problem.reported_objective()),
+			);
+
+			if update_best(&mut self.best, &problem, self.observer) {
+				self.queue.on_incumbent(self.best.objective.to_f32());
+			}
 
-	println!("Branch and bound completed. Results written to 'branches.txt'");
-	Ok(())
-}
+			return None;
+		}
 
-fn create_left_branch(
-	parent: &Problem,
-	branch_var_idx: i32,
-	branch_var_value: f32,
-	new_var_count: i32,
-	writer: &mut BufWriter<File>,
-) -> std::io::Result<Option<Problem>> {
-	let mut lt_con_coef = parent.con_coef.clone();
-	lt_con_coef.resize((parent.con_coef.rows + 1, parent.con_coef.columns + 1));
-
-	let mut lt_con_rhs_coef = parent.con_rhs_coef.clone();
-	lt_con_rhs_coef.resize((parent.con_rhs_coef.rows + 1, 1));
-
-	let mut lt_obj_coef = parent.obj_coef.clone();
-	lt_obj_coef.resize((1, parent.obj_coef.columns + 1));
-	let mut lt_obj_rhs = parent.obj_rhs.clone();
-
-	let branch_var_row_idx = (0..lt_con_coef.rows)
-		.find(|&i| lt_con_coef.get((i, branch_var_idx as usize)) == 1.0)
-		.expect("Branching variable not found in constraints");
-
-	// setup the new constraint
-	let lt_new_con_row = (0..lt_con_coef.columns)
-		.map(|j| {
-			if j == branch_var_idx as usize || j == lt_con_coef.columns - 1 {
-				1.0
-			} else {
-				0.0
-			}
+		let approximate = candidates
+			.iter()
+			.map(|c| Candidate {
+				var: c.var,
+				value: c.value.to_f32(),
+			})
+			.collect::<Vec<_>>();
+
+		let parent_bound = problem.objective().to_f32();
+		let chosen = self
+			.rule
+			.select(parent_bound, &approximate, &mut |candidate| {
+				let candidate = candidates
+					.iter()
+					.find(|c| c.var == candidate.var)
+					.expect("rules only probe the candidates they were given");
+				probe_children(&problem, candidate)
+			});
+		let candidate = candidates[chosen];
+
+		self.observer.on_event(&SolverEvent::Branching {
+			section: &problem.section,
+			column: candidate.var,
+			variable: &problem.tableau.variable_map[candidate.var].name,
+			value: candidate.value,
+		});
+
+		Some(Branch {
+			problem: Arc::new(problem),
+			candidate,
+			approximate: approximate[chosen],
+			children: ChildBounds {
+				down: None,
+				up: None,
+			},
 		})
-		.collect::<Vec<_>>();
+	}
 
-	lt_con_rhs_coef.set((lt_con_rhs_coef.rows - 1, 0), -branch_var_value);
-	for (j, &value) in lt_new_con_row.iter().enumerate() {
-		let branch_row_coef = lt_con_coef.get((branch_var_row_idx, j));
-		lt_con_coef.set((lt_con_coef.rows - 1, j), (branch_row_coef - value) * -1.0);
-	}
-
-	let mut new_variable_map = parent.variable_map.clone();
-	let x_count = new_variable_map
-		.iter()
-		.filter(|v| v.var_type == "x")
-		.count();
-	let slack_index = new_variable_map.len() - x_count;
-	new_variable_map.push(VariableInfo {
-		var_type: "s".to_string(),
-		index: slack_index,
-	});
+	/// Reports the child about to be solved, returning its section.
+	fn announce(&mut self, branch: &Branch<T>, direction: BranchDirection) -> String {
+		let (suffix, bound) = match direction {
+			BranchDirection::Down => (1, branch.candidate.value.floor()),
+			BranchDirection::Up => (2, branch.candidate.value.ceil()),
+		};
+
+		let section = format!("{}.{}", branch.problem.section, suffix);
+		self.observer.on_event(&SolverEvent::BranchCreated {
+			parent: &branch.problem.section,
+			section: &section,
+			variable: &branch.problem.tableau.variable_map[branch.candidate.var].name,
+			direction,
+			bound,
+		});
+
+		section
+	}
 
-	let result = get_optimal_dual(
-		&mut lt_con_coef,
-		&mut lt_con_rhs_coef,
-		&mut lt_obj_coef,
-		&mut lt_obj_rhs,
-		writer,
-		&new_variable_map,
-	);
+	/// Queues a solved child of the branch, or closes it when it can't beat the incumbent or
+	/// failed. `section` is what [`Search::announce`] returned for the child.
+	fn settle(
+		&mut self,
+		branch: &mut Branch<T>,
+		direction: BranchDirection,
+		section: &str,
+		child: Result<Problem<T>, SolveError>,
+	) {
+		let child_bound = child
+			.as_ref()
+			.ok()
+			.map(|problem| problem.objective().to_f32());
+		match direction {
+			BranchDirection::Down => branch.children.down = child_bound,
+			BranchDirection::Up => branch.children.up = child_bound,
+		}
 
-	if result.is_ok() {
-		let obj_value = lt_obj_rhs.get((0, 0));
-		writeln!(
-			writer,
-			"Problem {}.1: Found optimal solution with objective value: {:.3}",
-			parent.section, obj_value
-		)?;
-
-		write!(writer, "Problem {}.1: Variable values: ", parent.section)?;
-		for j in 0..lt_con_coef.columns {
-			let mut one_row: Option<usize> = None;
-			let mut is_basic = true;
-
-			for i in 0..lt_con_coef.rows {
-				let val = lt_con_coef.get((i, j));
-				if (val - 1.0).abs() < 1e-6 {
-					if one_row.is_none() {
-						one_row = Some(i);
-					} else {
-						is_basic = false;
-						break;
-					}
-				} else if val.abs() > 1e-6 {
-					is_basic = false;
-					break;
-				}
+		match child {
+			Ok(problem) if can_improve(&self.best, problem.objective(), self.options) => {
+				self.next_id += 1;
+				self.snapshots.insert(self.next_id, Snapshot::of(&problem));
+				self.queue.push(Node::new(self.next_id, &problem));
+			}
+			Ok(problem) => prune(
+				&mut self.pruned,
+				self.observer,
+				&problem.section,
+				PruneReason::Bound,
+				Some(problem.reported_objective()),
+			),
+			Err(error) => {
+				self.abandon(section, branch.problem.objective(), error);
 			}
+		}
+	}
 
-			let value = if is_basic {
-				if let Some(row) = one_row {
-					lt_con_rhs_coef.get((row, 0))
-				} else {
-					0.0
-				}
-			} else {
-				0.0
-			};
+	/// Closes a node whose relaxation failed. Unless it is infeasible its subtree is left unexplored,
+	/// so `bound`, the bound of the relaxation it came from, still counts towards the solution's.
+	fn abandon(&mut self, section: &str, bound: T, error: SolveError) {
+		if !matches!(error, SolveError::Infeasible { .. }) {
+			self.abandoned.push((bound, error));
+		}
 
-			let var_name = new_variable_map
-				.get(j)
-				.map(|v| format!("{}{}", v.var_type, v.index + 1))
-				.unwrap_or_else(|| format!("var{}", j));
+		prune(&mut self.pruned, self.observer, section, error.into(), None);
+	}
 
-			write!(writer, "{} = {:.3} ", var_name, value)?;
-		}
+	/// Tells the branching rule how the children of the branch turned out.
+	fn close(&mut self, branch: Branch<T>) {
+		self.rule.observe(
+			branch.problem.objective().to_f32(),
+			&branch.approximate,
+			&branch.children,
+		);
+	}
 
-		writeln!(writer)?;
+	fn finish(self) -> Solution<T> {
+		let Search {
+			root,
+			options,
+			observer,
+			snapshots,
+			mut best,
+			pruned,
+			abandoned,
+			..
+		} = self;
+
+		// nodes still queued and abandoned subtrees may hold better solutions, unless their bound
+		// rules that out
+		let (interrupted, failed): (Vec<_>, Vec<_>) = abandoned
+			.into_iter()
+			.filter(|&(bound, _)| can_improve(&best, bound, options))
+			.partition(|(_, error)| error.is_interrupted());
+		let stopped = snapshots
+			.values()
+			.map(|snapshot| snapshot.bound)
+			.filter(|&bound| can_improve(&best, bound, options))
+			.chain(interrupted.iter().map(|&(bound, _)| bound))
+			.reduce(|a, b| a.max(b));
+		let failed = failed
+			.into_iter()
+			.reduce(|a, b| if b.0 > a.0 { b } else { a });
+
+		best.status = match (stopped, failed) {
+			(None, None) => best.status,
+			_ if options.cancel.is_cancelled() => SolutionStatus::Cancelled,
+			(Some(_), _) => SolutionStatus::LimitReached,
+			(None, Some((_, error))) => SolutionStatus::Failed(error),
+		};
+		best.bound = stopped
+			.into_iter()
+			.chain(failed.map(|(bound, _)| bound))
+			.reduce(|a, b| a.max(b))
+			.unwrap_or(best.objective);
+
+		best.objective *= root.tableau.sense.signum::<T>();
+		best.bound *= root.tableau.sense.signum::<T>();
+		best.pruned = pruned;
+		observer.on_event(&SolverEvent::Finished { solution: &best });
+		best
+	}
+}
 
-		Ok(Some(Problem {
-			var_count: new_var_count,
-			con_coef: lt_con_coef,
-			con_rhs_coef: lt_con_rhs_coef,
-			obj_coef: lt_obj_coef,
-			obj_rhs: lt_obj_rhs,
-			section: format!("{}.1", parent.section),
-			variable_map: new_variable_map,
-		}))
-	} else {
-		writeln!(
-			writer,
-			"Problem {}.1: Infeasible or unbounded",
-			parent.section
-		)?;
-		Ok(None)
-	}
-}
-
-fn create_right_branch(
-	parent: &Problem,
-	branch_var_idx: i32,
-	branch_var_value: f32,
-	new_var_count: i32,
-	writer: &mut BufWriter<File>,
-) -> std::io::Result<Option<Problem>> {
-	let mut gt_con_coef = parent.con_coef.clone();
-	gt_con_coef.resize((parent.con_coef.rows + 1, parent.con_coef.columns + 1));
-
-	let mut gt_con_rhs_coef = parent.con_rhs_coef.clone();
-	gt_con_rhs_coef.resize((parent.con_rhs_coef.rows + 1, 1));
-
-	let mut gt_obj_coef = parent.obj_coef.clone();
-	gt_obj_coef.resize((1, parent.obj_coef.columns + 1));
-	let mut gt_obj_rhs = parent.obj_rhs.clone();
-
-	let branch_var_row_idx = (0..gt_con_coef.rows)
-		.find(|&i| gt_con_coef.get((i, branch_var_idx as usize)) == 1.0)
-		.expect("Branching variable not found in constraints");
-
-	// setup the new constraint
-	let gt_new_con_row = (0..gt_con_coef.columns)
-		.map(|j| {
-			if j == branch_var_idx as usize {
-				1.0
-			} else if j == gt_con_coef.columns - 1 {
-				-1.0
-			} else {
-				0.0
-			}
-		})
-		.collect::<Vec<_>>();
+/// Records a closed node and reports it.
+fn prune<T: Number>(
+	pruned: &mut Vec<PrunedNode>,
+	observer: &mut dyn SolverObserver<T>,
+	section: &str,
+	reason: PruneReason,
+	bound: Option<T>,
+) {
+	observer.on_event(&SolverEvent::NodePruned {
+		section,
+		reason: &reason,
+		bound,
+	});
 
-	dbg!(branch_var_value);
-	gt_con_rhs_coef.set((gt_con_rhs_coef.rows - 1, 0), branch_var_value - 1.0);
-	for (j, &value) in gt_new_con_row.iter().enumerate() {
-		let branch_row_coef = gt_con_coef.get((branch_var_row_idx, j));
-		gt_con_coef.set((gt_con_coef.rows - 1, j), branch_row_coef - value);
-	}
-
-	let mut new_variable_map = parent.variable_map.clone();
-	let x_count = new_variable_map
-		.iter()
-		.filter(|v| v.var_type == "x")
-		.count();
-	let excess_index = new_variable_map.len() - x_count;
-	new_variable_map.push(VariableInfo {
-		var_type: "e".to_string(),
-		index: excess_index,
+	pruned.push(PrunedNode {
+		section: section.to_string(),
+		reason,
 	});
+}
 
-	let result = get_optimal_dual(
-		&mut gt_con_coef,
-		&mut gt_con_rhs_coef,
-		&mut gt_obj_coef,
-		&mut gt_obj_rhs,
-		writer,
-		&new_variable_map,
-	);
+/// Whether a node with the given relaxation bound could still beat the incumbent by more than the
+/// configured gap.
+fn can_improve<T: Number>(incumbent: &Solution<T>, bound: T, options: &SolverOptions) -> bool {
+	if incumbent.status == SolutionStatus::Infeasible {
+		return true;
+	}
 
-	if result.is_ok() {
-		let obj_value = gt_obj_rhs.get((0, 0));
-		writeln!(
-			writer,
-			"Problem {}.2: Found optimal solution with objective value: {:.3}",
-			parent.section, obj_value
-		)?;
-
-		write!(writer, "Problem {}.2: Variable values: ", parent.section)?;
-		for j in 0..gt_con_coef.columns {
-			let mut one_row: Option<usize> = None;
-			let mut is_basic = true;
-
-			for i in 0..gt_con_coef.rows {
-				let val = gt_con_coef.get((i, j));
-				if (val - 1.0).abs() < 1e-6 {
-					if one_row.is_none() {
-						one_row = Some(i);
-					} else {
-						is_basic = false;
-						break;
-					}
-				} else if val.abs() > 1e-6 {
-					is_basic = false;
-					break;
-				}
-			}
+	let gap =
+		T::from_f32(options.abs_gap).max(T::from_f32(options.rel_gap) * incumbent.objective.abs());
+	bound - incumbent.objective > gap
+}
 
-			let value = if is_basic {
-				if let Some(row) = one_row {
-					gt_con_rhs_coef.get((row, 0))
-				} else {
-					0.0
-				}
-			} else {
-				0.0
-			};
+/// Replaces the incumbent if the given integral problem improves on it, returning whether it did.
+fn update_best<T: Number>(
+	best: &mut Solution<T>,
+	problem: &Problem<T>,
+	observer: &mut dyn SolverObserver<T>,
+) -> bool {
+	let objective = problem.objective();
+	if best.status == SolutionStatus::Infeasible || objective > best.objective {
+		*best = Solution {
+			status: SolutionStatus::Optimal,
+			objective,
+			bound: objective,
+			values: problem.x_values(),
+			names: problem.x_names(),
+			section: problem.section.clone(),
+			pruned: Vec::new(),
+		};
+
+		observer.on_event(&SolverEvent::IncumbentImproved {
+			section: &problem.section,
+			objective: problem.reported_objective(),
+		});
+
+		return true;
+	}
+
+	false
+}
+
+/// Solves both children of a branch without reporting them, for rules that look ahead.
+fn probe_children<T: Number>(parent: &Problem<T>, candidate: &Candidate<T>) -> ChildBounds {
+	let solve = |direction| {
+		create_branch(parent, candidate, direction, &mut ())
+			.ok()
+			.map(|problem| problem.objective().to_f32())
+	};
+
+	ChildBounds {
+		down: solve(BranchDirection::Down),
+		up: solve(BranchDirection::Up),
+	}
+}
 
-			let var_name = new_variable_map
-				.get(j)
-				.map(|v| format!("{}{}", v.var_type, v.index + 1))
-				.unwrap_or_else(|| format!("var{}", j));
+/// Child of `parent` with the candidate's bounds tightened to below or above its value,
+/// re-optimised with the dual simplex.
+fn create_branch<T: Number>(
+	parent: &Problem<T>,
+	candidate: &Candidate<T>,
+	direction: BranchDirection,
+	observer: &mut dyn SolverObserver<T>,
+) -> Result<Problem<T>, SolveError> {
+	let column = candidate.var;
+	let (suffix, lower, upper) = match direction {
+		BranchDirection::Down => (
+			1,
+			parent.tableau.bounds.lower[column],
+			candidate.value.floor(),
+		),
+		BranchDirection::Up => (
+			2,
+			candidate.value.ceil(),
+			parent.tableau.bounds.upper[column],
+		),
+	};
 
-			write!(writer, "{} = {:.3} ", var_name, value)?;
+	let mut child = parent.clone();
+	child.section = format!("{}.{}", parent.section, suffix);
+	child.changes.push(BoundChange {
+		column,
+		lower,
+		upper,
+	});
+	set_column_bounds(&mut child.tableau, column, lower, upper);
+
+	let start = Instant::now();
+	let result = get_optimal_dual(&mut child.tableau, &child.options, observer);
+
+	let iterations = match result {
+		Ok(iterations) => iterations,
+		Err(error) => {
+			observer.on_event(&SolverEvent::LpFailed {
+				section: &child.section,
+				error,
+				time: start.elapsed(),
+			});
+			return Err(error);
 		}
+	};
 
-		writeln!(writer)?;
+	observer.on_event(&SolverEvent::LpSolved {
+		section: &child.section,
+		objective: child.reported_objective(),
+		values: &child.values(),
+		variables: &child.tableau.variable_map,
+		iterations,
+		time: start.elapsed(),
+	});
 
-		Ok(Some(Problem {
-			var_count: new_var_count,
-			con_coef: gt_con_coef,
-			con_rhs_coef: gt_con_rhs_coef,
-			obj_coef: gt_obj_coef,
-			obj_rhs: gt_obj_rhs,
-			section: format!("{}.2", parent.section),
-			variable_map: new_variable_map,
-		}))
-	} else {
-		writeln!(
-			writer,
-			"Problem {}.2: Infeasible or unbounded",
-			parent.section
-		)?;
-		Ok(None)
-	}
+	Ok(child)
 }
 
-pub fn print_tableau(
-	con_coef: &Compressed<f32>,
-	con_rhs_coef: &Compressed<f32>,
-	obj_coef: &Compressed<f32>,
-	rhs_coef: &Compressed<f32>,
+pub fn print_tableau<T: Number>(
+	con_coef: &Compressed<T>,
+	con_rhs_coef: &Compressed<T>,
+	obj_coef: &Compressed<T>,
+	rhs_coef: &Compressed<T>,
 ) {
-	obj_coef.values.iter().enumerate().for_each(|(i, &value)| {
+	obj_coef.values.iter().for_each(|&value| {
 		print!("|");
 		print!("{:8.2} ", value);
 	});
@@ -725,7 +1249,7 @@ pub fn print_tableau(
 	println!();
 }
 
-pub fn print_comp_matrix(matrix: &Compressed<f32>) {
+pub fn print_comp_matrix<T: Number>(matrix: &Compressed<T>) {
 	for i in 0..matrix.rows {
 		for j in 0..matrix.columns {
 			print!("{:8.2} ", matrix.get((i, j)));
@@ -735,7 +1259,7 @@ pub fn print_comp_matrix(matrix: &Compressed<f32>) {
 	}
 }
 
-pub fn print_conv_matrix(matrix: &Conventional<f32>) {
+pub fn print_conv_matrix<T: Number>(matrix: &Conventional<T>) {
 	for i in 0..matrix.rows {
 		for j in 0..matrix.columns {
 			print!("{:8.2} ", matrix[(i, j)]);
@@ -0,0 +1,592 @@
+use crate::{
+	Bounds, ConstraintSensitivity, Number, Sensitivity, Solution, SolveError, SolverObserver,
+	SolverOptions, Tableau, TextLog, VariableInfo, VariableSensitivity, get_bounded_values,
+	get_objective_range, get_optimal_two_phase, get_reduced_costs, get_rhs_range, set_column_bounds,
+};
+use matrix::{Matrix, format::Compressed};
+use std::io::{self, Write};
+
+/// Direction of the objective.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sense {
+	#[default]
+	Maximise,
+	Minimise,
+}
+
+impl Sense {
+	/// Multiplier turning an objective value in this sense into maximisation form and back.
+	pub fn signum<T: Number>(self) -> T {
+		match self {
+			Sense::Maximise => T::one(),
+			Sense::Minimise => -T::one(),
+		}
+	}
+}
+
+/// Relation between the left and right-hand side of a constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+	LessEqual,
+	GreaterEqual,
+	Equal,
+}
+
+/// Handle to a variable added to a [`Model`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+#[derive(Clone, Debug)]
+struct Variable<T> {
+	name: String,
+	lower: T,
+	upper: T,
+	integer: bool,
+	objective: T,
+}
+
+impl<T: Number> Variable<T> {
+	/// Bounds used in the tableau, integer variables can have theirs rounded inwards.
+	fn bounds(&self) -> (T, T) {
+		if self.integer {
+			(self.lower.ceil(), self.upper.floor())
+		} else {
+			(self.lower, self.upper)
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Constraint<T> {
+	name: String,
+	terms: Vec<(Var, T)>,
+	relation: Relation,
+	rhs: T,
+}
+
+/// Linear or mixed integer program built from named variables and constraints, solved in the
+/// number type `T`. Models in `f64` or exact [`crate::Rational`]s are created with
+/// [`Model::default`], e.g. `Model::<Rational>::default()`.
+#[derive(Clone, Debug, Default)]
+pub struct Model<T = f32> {
+	sense: Sense,
+	variables: Vec<Variable<T>>,
+	constraints: Vec<Constraint<T>>,
+	options: SolverOptions,
+}
+
+impl Model {
+	/// Model solved in `f32`.
+	pub fn new() -> Self {
+		Model::default()
+	}
+}
+
+impl<T: Number> Model<T> {
+	pub fn with_options(mut self, options: SolverOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Adds a variable bounded by `lower <= x <= upper`, `upper` may be infinite.
+	///
+	/// # Panics
+	///
+	/// Panics if `lower` is not finite.
+	pub fn add_variable(&mut self, name: &str, lower: T, upper: T, integer: bool) -> Var {
+		assert!(
+			lower.is_finite(),
+			"variable {name} needs a finite lower bound"
+		);
+
+		self.variables.push(Variable {
+			name: name.to_string(),
+			lower,
+			upper,
+			integer,
+			objective: T::zero(),
+		});
+
+		Var(self.variables.len() - 1)
+	}
+
+	/// Adds `Σ coef * var <relation> rhs`.
+	pub fn add_constraint(&mut self, name: &str, terms: &[(Var, T)], relation: Relation, rhs: T) {
+		self.constraints.push(Constraint {
+			name: name.to_string(),
+			terms: terms.to_vec(),
+			relation,
+			rhs,
+		});
+	}
+
+	/// Replaces the objective, variables missing from `terms` get a zero coefficient.
+	pub fn set_objective(&mut self, sense: Sense, terms: &[(Var, T)]) {
+		self.sense = sense;
+		for variable in &mut self.variables {
+			variable.objective = T::zero();
+		}
+
+		for &(Var(j), coef) in terms {
+			self.variables[j].objective += coef;
+		}
+	}
+
+	/// Lowers the model into a tableau. `>=` rows are negated and every row except the `=` rows
+	/// receives a slack column named after it, the two-phase simplex takes care of the rows that are
+	/// left without a starting basis. Variable bounds go into [`Tableau::bounds`] rather than rows,
+	/// only a variable whose bounds cross gets an upper bound row, which makes the model infeasible.
+	pub fn tableau(&self) -> Tableau<T> {
+		self.lower(false)
+	}
+
+	/// Lowers the model as [`Model::tableau`] does, giving `=` rows a slack column fixed at zero too
+	/// with `equality_slacks`, so every constraint can be traced to a column of the solved tableau.
+	fn lower(&self, equality_slacks: bool) -> Tableau<T> {
+		let n = self.variables.len();
+
+		// rows hold the name of their slack column, equality rows have none
+		let mut rows: Vec<(Option<String>, Vec<T>, T)> = Vec::new();
+		for constraint in &self.constraints {
+			let mut coefs = vec![T::zero(); n];
+			for &(Var(j), coef) in &constraint.terms {
+				coefs[j] += coef;
+			}
+
+			let rhs = constraint.rhs;
+			let slack = Some(format!("s({})", constraint.name));
+			match constraint.relation {
+				Relation::LessEqual => rows.push((slack, coefs, rhs)),
+				Relation::GreaterEqual => rows.push((slack, coefs.iter().map(|&a| -a).collect(), -rhs)),
+				Relation::Equal => rows.push((slack.filter(|_| equality_slacks), coefs, rhs)),
+			}
+		}
+
+		let mut column_bounds = Vec::with_capacity(n);
+		for (j, variable) in self.variables.iter().enumerate() {
+			let (lower, upper) = variable.bounds();
+			if upper < lower {
+				let mut coefs = vec![T::zero(); n];
+				coefs[j] = T::one();
+				rows.push((
+					Some(format!("s({} ≤ {})", variable.name, upper)),
+					coefs,
+					upper,
+				));
+				column_bounds.push((lower, T::infinity()));
+			} else {
+				column_bounds.push((lower, upper));
+			}
+		}
+
+		let m = rows.len();
+		let slacks = rows.iter().filter(|(slack, _, _)| slack.is_some()).count();
+		let mut obj_coef = Compressed::<T>::zero((1, n + slacks));
+		let mut obj_rhs = Compressed::<T>::zero((1, 1));
+		let mut con_coef = Compressed::<T>::zero((m, n + slacks));
+		let mut con_rhs_coef = Compressed::<T>::zero((m, 1));
+
+		for (j, variable) in self.variables.iter().enumerate() {
+			obj_coef.set((0, j), -variable.objective);
+		}
+
+		obj_rhs.set((0, 0), T::zero());
+
+		let mut variable_map = self
+			.variables
+			.iter()
+			.enumerate()
+			.map(|(j, v)| VariableInfo {
+				var_type: "x".to_string(),
+				index: j,
+				name: v.name.clone(),
+				integer: v.integer,
+			})
+			.collect::<Vec<_>>();
+
+		// slack columns start out basic in their rows
+		let mut basis = vec![None; m];
+		for (i, (slack, coefs, rhs)) in rows.into_iter().enumerate() {
+			for (j, coef) in coefs.into_iter().enumerate() {
+				if !coef.is_zero() {
+					con_coef.set((i, j), coef);
+				}
+			}
+
+			con_rhs_coef.set((i, 0), rhs);
+			if let Some(name) = slack {
+				let index = variable_map.len() - n;
+				con_coef.set((i, n + index), T::one());
+				basis[i] = Some(n + index);
+				variable_map.push(VariableInfo {
+					var_type: "s".to_string(),
+					index,
+					name,
+					integer: false,
+				});
+			}
+		}
+
+		let mut tableau = Tableau {
+			sense: self.sense,
+			var_count: n as i32,
+			con_coef,
+			con_rhs_coef,
+			obj_coef,
+			obj_rhs,
+			bounds: Bounds::free(n + slacks),
+			basis,
+			variable_map,
+		};
+
+		// measuring every variable from its lower bound moves the rows' right-hand sides and the
+		// objective's constant
+		for (j, (lower, upper)) in column_bounds.into_iter().enumerate() {
+			set_column_bounds(&mut tableau, j, lower, upper);
+		}
+
+		// constraints come first, so with every one of them having a slack they line up with the
+		// slack columns
+		if equality_slacks {
+			for (i, constraint) in self.constraints.iter().enumerate() {
+				if constraint.relation == Relation::Equal {
+					tableau.bounds.upper[n + i] = T::zero();
+				}
+			}
+		}
+
+		tableau
+	}
+
+	/// Solves the model, reporting the objective and variable values in the model's own terms.
+	pub fn solve(&self) -> Solution<T> {
+		self.solve_with_observer(&mut ())
+	}
+
+	/// Like [`Model::solve`], additionally logging every branch to `writer`, see [`TextLog`].
+	pub fn solve_with_log<W: Write>(&self, writer: &mut W) -> io::Result<Solution<T>> {
+		let mut log = TextLog::new(writer);
+		let solution = self.solve_with_observer(&mut log);
+		log.finish()?;
+		Ok(solution)
+	}
+
+	/// Like [`Model::solve`], reporting progress to `observer`.
+	pub fn solve_with_observer(&self, observer: &mut dyn SolverObserver<T>) -> Solution<T> {
+		self.tableau().solve(&self.options, observer)
+	}
+
+	/// Solves the linear relaxation, ignoring integrality, and reports the reduced costs, shadow
+	/// prices and the ranges over which they hold, see [`Sensitivity`]. These answer what-if
+	/// questions like how much a constraint's right-hand side is worth without solving again.
+	pub fn sensitivity(&self) -> Result<Sensitivity<T>, SolveError> {
+		let n = self.variables.len();
+		let mut tableau = self.lower(true).maximising();
+		get_optimal_two_phase(&mut tableau, &self.options)?;
+
+		let tolerances = &self.options.tolerances;
+		let signum = self.sense.signum::<T>();
+		let values = get_bounded_values(&tableau.con_rhs_coef, &tableau.bounds, &tableau.basis);
+		let reduced_costs = get_reduced_costs(&tableau.obj_coef, &tableau.bounds);
+
+		let variables = self
+			.variables
+			.iter()
+			.enumerate()
+			.map(|(j, variable)| {
+				let (decrease, increase) = get_objective_range(
+					&tableau.con_coef,
+					&tableau.obj_coef,
+					&tableau.bounds,
+					&tableau.basis,
+					j,
+					tolerances,
+				);
+
+				// minimising turns the objective around
+				let (decrease, increase) = match self.sense {
+					Sense::Maximise => (decrease, increase),
+					Sense::Minimise => (increase, decrease),
+				};
+
+				VariableSensitivity {
+					name: variable.name.clone(),
+					value: values[j],
+					reduced_cost: signum * reduced_costs[j],
+					objective_range: (variable.objective - decrease, variable.objective + increase),
+				}
+			})
+			.collect();
+
+		let constraints = self
+			.constraints
+			.iter()
+			.enumerate()
+			.map(|(i, constraint)| {
+				let (decrease, increase) = get_rhs_range(
+					&tableau.con_coef,
+					&tableau.con_rhs_coef,
+					&tableau.bounds,
+					&tableau.basis,
+					n + i,
+					tolerances,
+				);
+
+				// the tableau holds `>=` rows negated
+				let price = -reduced_costs[n + i];
+				let (price, decrease, increase) = match constraint.relation {
+					Relation::GreaterEqual => (-price, increase, decrease),
+					_ => (price, decrease, increase),
+				};
+
+				ConstraintSensitivity {
+					name: constraint.name.clone(),
+					rhs: constraint.rhs,
+					shadow_price: signum * price,
+					rhs_range: (constraint.rhs - decrease, constraint.rhs + increase),
+				}
+			})
+			.collect();
+
+		Ok(Sensitivity {
+			objective: signum * tableau.obj_rhs.get((0, 0)),
+			variables,
+			constraints,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{NodeSelection, PivotRule, Rational, SolutionStatus};
+
+	/// Integer program over `lower[j] <= x[j] <= upper[j]` with `<=` rows.
+	struct Instance {
+		lower: Vec<i64>,
+		upper: Vec<i64>,
+		objective: Vec<i64>,
+		rows: Vec<(Vec<i64>, i64)>,
+		sense: Sense,
+	}
+
+	impl Instance {
+		fn model<T: Number + Default>(&self, node_selection: NodeSelection) -> Model<T> {
+			let int = |value: i64| T::from_f32(value as f32);
+			let mut model = Model::<T>::default().with_options(SolverOptions {
+				node_selection,
+				..Default::default()
+			});
+			let vars = (0..self.lower.len())
+				.map(|j| {
+					model.add_variable(
+						&format!("x{j}"),
+						int(self.lower[j]),
+						int(self.upper[j]),
+						true,
+					)
+				})
+				.collect::<Vec<_>>();
+			let terms = |coefs: &[i64]| {
+				vars
+					.iter()
+					.zip(coefs)
+					.map(|(&var, &coef)| (var, int(coef)))
+					.collect::<Vec<_>>()
+			};
+
+			for (i, (coefs, rhs)) in self.rows.iter().enumerate() {
+				model.add_constraint(
+					&format!("c{i}"),
+					&terms(coefs),
+					Relation::LessEqual,
+					int(*rhs),
+				);
+			}
+
+			model.set_objective(self.sense, &terms(&self.objective));
+			model
+		}
+
+		/// Best objective value over every integer point, `None` if there is none.
+		fn brute_force(&self) -> Option<i64> {
+			let mut x = self.lower.clone();
+			let mut best = None::<i64>;
+			loop {
+				let feasible = self
+					.rows
+					.iter()
+					.all(|(coefs, rhs)| coefs.iter().zip(&x).map(|(a, x)| a * x).sum::<i64>() <= *rhs);
+				if feasible {
+					let value = self.objective.iter().zip(&x).map(|(c, x)| c * x).sum();
+					best = Some(match (best, self.sense) {
+						(None, _) => value,
+						(Some(best), Sense::Maximise) => best.max(value),
+						(Some(best), Sense::Minimise) => best.min(value),
+					});
+				}
+
+				// next point, counting through the box like an odometer
+				let Some(j) = (0..x.len()).find(|&j| x[j] < self.upper[j]) else {
+					return best;
+				};
+				x[j] += 1;
+				x[..j].copy_from_slice(&self.lower[..j]);
+			}
+		}
+	}
+
+	fn random_instances() -> Vec<Instance> {
+		// xorshift, so the instances are the same on every run
+		let mut state = 0x9e37_79b9_u64;
+		let mut next = |low: i64, high: i64| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			low + (state % (high - low + 1) as u64) as i64
+		};
+
+		(0..60)
+			.map(|_| {
+				let n = next(2, 4) as usize;
+				let lower = (0..n).map(|_| next(1, 3)).collect::<Vec<_>>();
+				let upper = lower.iter().map(|&l| l + next(1, 4)).collect();
+				let objective = (0..n).map(|_| next(-3, 9)).collect();
+				let rows = (0..next(1, 3))
+					.map(|_| ((0..n).map(|_| next(-2, 7)).collect(), next(5, 40)))
+					.collect();
+				let sense = if next(0, 1) == 0 {
+					Sense::Maximise
+				} else {
+					Sense::Minimise
+				};
+
+				Instance {
+					lower,
+					upper,
+					objective,
+					rows,
+					sense,
+				}
+			})
+			.collect()
+	}
+
+	fn check_against_brute_force<T: Number + Default>() {
+		for (i, instance) in random_instances().iter().enumerate() {
+			let expected = instance.brute_force();
+			// best bound jumps around the tree, so most nodes are rebuilt from the root's tableau
+			for selection in [NodeSelection::DepthFirst, NodeSelection::BestBound] {
+				let solution = instance.model::<T>(selection).solve();
+				match expected {
+					None => assert_eq!(solution.status, SolutionStatus::Infeasible, "instance {i}"),
+					Some(expected) => {
+						assert_eq!(solution.status, SolutionStatus::Optimal, "instance {i}");
+						assert!(
+							(solution.objective.to_f64() - expected as f64).abs() < 1e-3,
+							"instance {i}: {} instead of {expected}",
+							solution.objective
+						);
+					}
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn shifted_and_general_integer_bounds_f32() {
+		check_against_brute_force::<f32>();
+	}
+
+	#[test]
+	fn shifted_and_general_integer_bounds_exact() {
+		check_against_brute_force::<Rational>();
+	}
+
+	#[test]
+	fn hand_solved_bounded_program() {
+		// maximise 3x + 2y with 2 <= x <= 5, 1 <= y <= 4 and x + y <= 7.5, 2x - y <= 6, whose
+		// relaxation is fractional at x = 4.5, y = 3 and whose integer optimum is x = 4, y = 3
+		let mut model = Model::<Rational>::default();
+		let x = model.add_variable("x", 2.into(), 5.into(), true);
+		let y = model.add_variable("y", 1.into(), 4.into(), true);
+		model.add_constraint(
+			"sum",
+			&[(x, 1.into()), (y, 1.into())],
+			Relation::LessEqual,
+			Rational::new(15, 2),
+		);
+		model.add_constraint(
+			"difference",
+			&[(x, 2.into()), (y, (-1).into())],
+			Relation::LessEqual,
+			6.into(),
+		);
+		model.set_objective(Sense::Maximise, &[(x, 3.into()), (y, 2.into())]);
+
+		let solution = model.solve();
+		assert_eq!(solution.status, SolutionStatus::Optimal);
+		assert_eq!(solution.objective, 18.into());
+		assert_eq!(solution.value("x"), Some(4.into()));
+		assert_eq!(solution.value("y"), Some(3.into()));
+	}
+
+	#[test]
+	fn pivot_rules_on_cycling_example() {
+		// Chvátal's example, on which the textbook largest coefficient rule cycles through six
+		// degenerate bases, optimal at x1 = x3 = 1
+		let half = Rational::new(1, 2);
+		for pivot_rule in [
+			PivotRule::Dantzig,
+			PivotRule::Bland,
+			PivotRule::Lexicographic,
+		] {
+			let mut model = Model::<Rational>::default().with_options(SolverOptions {
+				pivot_rule,
+				pivot_limit: 20,
+				..Default::default()
+			});
+			let x = (1..=4)
+				.map(|j| model.add_variable(&format!("x{j}"), 0.into(), Rational::infinity(), false))
+				.collect::<Vec<_>>();
+			model.add_constraint(
+				"a",
+				&[
+					(x[0], half),
+					(x[1], Rational::new(-11, 2)),
+					(x[2], Rational::new(-5, 2)),
+					(x[3], 9.into()),
+				],
+				Relation::LessEqual,
+				0.into(),
+			);
+			model.add_constraint(
+				"b",
+				&[
+					(x[0], half),
+					(x[1], Rational::new(-3, 2)),
+					(x[2], -half),
+					(x[3], 1.into()),
+				],
+				Relation::LessEqual,
+				0.into(),
+			);
+			model.add_constraint("c", &[(x[0], 1.into())], Relation::LessEqual, 1.into());
+			model.set_objective(
+				Sense::Maximise,
+				&[
+					(x[0], 10.into()),
+					(x[1], (-57).into()),
+					(x[2], (-9).into()),
+					(x[3], (-24).into()),
+				],
+			);
+
+			let solution = model.solve();
+			assert_eq!(solution.status, SolutionStatus::Optimal, "{pivot_rule:?}");
+			assert_eq!(solution.objective, 1.into(), "{pivot_rule:?}");
+			assert_eq!(solution.value("x1"), Some(1.into()), "{pivot_rule:?}");
+			assert_eq!(solution.value("x3"), Some(1.into()), "{pivot_rule:?}");
+		}
+	}
+}
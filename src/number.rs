@@ -0,0 +1,120 @@
+use matrix::Element;
+use std::{
+	fmt,
+	ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Numeric type the simplex and branch and bound compute with, see [`crate::Rational`] for exact
+/// arithmetic.
+pub trait Number:
+	Element
+	+ PartialOrd
+	+ fmt::Debug
+	+ fmt::Display
+	+ Add<Output = Self>
+	+ Sub<Output = Self>
+	+ Mul<Output = Self>
+	+ Div<Output = Self>
+	+ Neg<Output = Self>
+	+ AddAssign
+	+ SubAssign
+	+ MulAssign
+	+ Send
+	+ Sync
+	+ 'static
+{
+	/// Whether arithmetic is free of rounding errors, in which case tolerances are zero.
+	const EXACT: bool;
+
+	fn one() -> Self;
+
+	fn infinity() -> Self;
+
+	/// Closest value to `value`, exact wherever the type allows.
+	fn from_f32(value: f32) -> Self;
+
+	/// Closest `f64`, for reporting and for heuristics that don't need exact values.
+	fn to_f64(self) -> f64;
+
+	/// Closest `f32`, which is what node selectors and branching rules work with.
+	fn to_f32(self) -> f32 {
+		self.to_f64() as f32
+	}
+
+	fn abs(self) -> Self;
+
+	fn floor(self) -> Self;
+
+	fn ceil(self) -> Self;
+
+	fn round(self) -> Self;
+
+	fn is_finite(self) -> bool;
+
+	/// `tolerance` in this type, or zero for exact types since they have no rounding errors to
+	/// tolerate.
+	fn tolerance(tolerance: f32) -> Self {
+		if Self::EXACT {
+			Self::zero()
+		} else {
+			Self::from_f32(tolerance)
+		}
+	}
+
+	/// The larger value, `self` when they can't be ordered.
+	fn max(self, other: Self) -> Self {
+		if other > self { other } else { self }
+	}
+
+	/// The smaller value, `self` when they can't be ordered.
+	fn min(self, other: Self) -> Self {
+		if other < self { other } else { self }
+	}
+}
+
+macro_rules! float {
+	($float:ident) => {
+		impl Number for $float {
+			const EXACT: bool = false;
+
+			fn one() -> Self {
+				1.0
+			}
+
+			fn infinity() -> Self {
+				$float::INFINITY
+			}
+
+			fn from_f32(value: f32) -> Self {
+				value as $float
+			}
+
+			fn to_f64(self) -> f64 {
+				self as f64
+			}
+
+			fn abs(self) -> Self {
+				$float::abs(self)
+			}
+
+			fn floor(self) -> Self {
+				$float::floor(self)
+			}
+
+			fn ceil(self) -> Self {
+				$float::ceil(self)
+			}
+
+			fn round(self) -> Self {
+				$float::round(self)
+			}
+
+			fn is_finite(self) -> bool {
+				$float::is_finite(self)
+			}
+		}
+	};
+}
+
+float!(f32);
+float!(f64);
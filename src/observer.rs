@@ -0,0 +1,300 @@
+use crate::{Number, PruneReason, Solution, SolutionStatus, SolveError, VariableInfo};
+use std::{
+	io::{self, Write},
+	time::Duration,
+};
+
+/// Side of a branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchDirection {
+	/// The child with the `x ≤ floor(value)` bound.
+	Down,
+	/// The child with the `x ≥ ceil(value)` bound.
+	Up,
+}
+
+/// Progress reported by [`crate::branch_and_bound`]. Objective values are in the problem's own
+/// sense, unlike the maximisation form handed to selectors and branching rules, and in the number
+/// type the solver computes with.
+#[derive(Clone, Copy, Debug)]
+pub enum SolverEvent<'a, T = f32> {
+	/// A node was taken off the queue.
+	NodeOpened {
+		section: &'a str,
+		depth: usize,
+		bound: T,
+	},
+	/// A variable was chosen to branch the node on.
+	Branching {
+		section: &'a str,
+		column: usize,
+		variable: &'a str,
+		value: T,
+	},
+	/// A child node was set up and is about to be solved.
+	BranchCreated {
+		parent: &'a str,
+		section: &'a str,
+		variable: &'a str,
+		direction: BranchDirection,
+		/// Right-hand side of the added bound.
+		bound: T,
+	},
+	/// The simplex pivoted, `iterations` counts the pivots of the current run including this one.
+	PivotPerformed {
+		column: usize,
+		row: usize,
+		variable: &'a str,
+		iterations: usize,
+		/// Whether the pivot was made by the dual simplex.
+		dual: bool,
+	},
+	/// A node's relaxation was solved to optimality.
+	LpSolved {
+		section: &'a str,
+		objective: T,
+		/// Value of every column, matching `variables`.
+		values: &'a [T],
+		variables: &'a [VariableInfo],
+		/// Pivots the simplex needed.
+		iterations: usize,
+		time: Duration,
+	},
+	/// The simplex failed on a node's relaxation.
+	LpFailed {
+		section: &'a str,
+		error: SolveError,
+		time: Duration,
+	},
+	/// A node was closed without being branched on.
+	NodePruned {
+		section: &'a str,
+		reason: &'a PruneReason,
+		/// Relaxation bound of the node, if it was solved.
+		bound: Option<T>,
+	},
+	IncumbentImproved {
+		section: &'a str,
+		objective: T,
+	},
+	/// The search is over, `solution` is what will be returned.
+	Finished {
+		solution: &'a Solution<T>,
+	},
+}
+
+/// Receives the progress of a solve computing with `T`.
+pub trait SolverObserver<T = f32> {
+	fn on_event(&mut self, event: &SolverEvent<T>);
+}
+
+/// Ignores every event.
+impl<T> SolverObserver<T> for () {
+	fn on_event(&mut self, _event: &SolverEvent<T>) {}
+}
+
+impl<T, O: SolverObserver<T> + ?Sized> SolverObserver<T> for &mut O {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		(**self).on_event(event);
+	}
+}
+
+/// Hands every event to both observers, in order.
+impl<T, A: SolverObserver<T>, B: SolverObserver<T>> SolverObserver<T> for (A, B) {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		self.0.on_event(event);
+		self.1.on_event(event);
+	}
+}
+
+/// Writes events as the human readable log that used to be written to `branches.txt`.
+///
+/// Observers can't fail, so the first write error is kept and returned by [`TextLog::finish`],
+/// later events are dropped.
+pub struct TextLog<W: Write> {
+	writer: W,
+	error: Option<io::Error>,
+	/// Objective value of the incumbent, already formatted.
+	incumbent: Option<String>,
+	opened: bool,
+}
+
+impl<W: Write> TextLog<W> {
+	pub fn new(writer: W) -> Self {
+		TextLog {
+			writer,
+			error: None,
+			incumbent: None,
+			opened: false,
+		}
+	}
+
+	/// Flushes the writer and returns the first error met while writing.
+	pub fn finish(mut self) -> io::Result<W> {
+		if let Some(error) = self.error.take() {
+			return Err(error);
+		}
+
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+
+	fn write<T: Number>(&mut self, event: &SolverEvent<T>) -> io::Result<()> {
+		let writer = &mut self.writer;
+		match *event {
+			SolverEvent::NodeOpened { section, .. } => {
+				// nodes are separated by a blank line
+				if self.opened {
+					writeln!(writer)?;
+				}
+
+				self.opened = true;
+				writeln!(writer, "=== Processing Problem {section} ===")?;
+			}
+			SolverEvent::Branching {
+				section,
+				column,
+				value,
+				..
+			} => {
+				writeln!(
+					writer,
+					"Problem {section}: Branching on variable {} with value {value}",
+					column + 1
+				)?;
+			}
+			SolverEvent::BranchCreated {
+				section,
+				variable,
+				direction,
+				bound,
+				..
+			} => {
+				let relation = match direction {
+					BranchDirection::Down => "≤",
+					BranchDirection::Up => "≥",
+				};
+
+				writeln!(
+					writer,
+					"--- Creating branch {section} ({variable} {relation} {bound}) ---"
+				)?;
+			}
+			SolverEvent::PivotPerformed {
+				row,
+				variable,
+				iterations,
+				dual,
+				..
+			} => {
+				if dual && iterations == 1 {
+					writeln!(
+						writer,
+						"<> Initial pivoting for dual problem with entering variable {variable} and leaving row {}",
+						row + 1
+					)?;
+				}
+			}
+			SolverEvent::LpSolved {
+				section,
+				objective,
+				values,
+				variables,
+				..
+			} => {
+				writeln!(
+					writer,
+					"Problem {section}: Found optimal solution with objective value: {objective:.3}"
+				)?;
+
+				write!(writer, "Problem {section}: Variable values: ")?;
+				for (j, value) in values.iter().enumerate() {
+					match variables.get(j) {
+						Some(variable) => write!(writer, "{} = {value:.3} ", variable.name)?,
+						None => write!(writer, "var{j} = {value:.3} ")?,
+					}
+				}
+
+				writeln!(writer)?;
+			}
+			SolverEvent::LpFailed { section, error, .. } => {
+				writeln!(writer, "Problem {section}: {error}")?;
+			}
+			SolverEvent::NodePruned {
+				section,
+				reason,
+				bound,
+			} => match (reason, bound) {
+				(PruneReason::Bound, Some(bound)) => {
+					writeln!(
+						writer,
+						"Problem {section}: Pruned, bound {bound:.3} does not improve on incumbent {}",
+						self.incumbent.as_deref().unwrap_or("NaN")
+					)?;
+				}
+				(PruneReason::Integral, Some(bound)) => {
+					writeln!(
+						writer,
+						"Problem {section}: All variables are integers, optimal solution found."
+					)?;
+					writeln!(writer, "Problem {section}: Objective value: {bound}")?;
+				}
+				// failed relaxations were already reported by `LpFailed`
+				_ => {}
+			},
+			SolverEvent::IncumbentImproved { section, objective } => {
+				self.incumbent = Some(format!("{objective:.3}"));
+				writeln!(
+					writer,
+					"Problem {section}: New incumbent with objective value: {objective:.3}"
+				)?;
+			}
+			SolverEvent::Finished { solution } => {
+				if self.opened {
+					writeln!(writer)?;
+				}
+
+				writeln!(writer, "=== Branch and Bound Algorithm Completed ===")?;
+				let stopped = match solution.status {
+					SolutionStatus::LimitReached => Some("Stopped at a limit".to_string()),
+					SolutionStatus::Cancelled => Some("Cancelled".to_string()),
+					SolutionStatus::Failed(error) => Some(format!("Failed ({error})")),
+					_ => None,
+				};
+
+				if let Some(reason) = stopped {
+					writeln!(
+						writer,
+						"{reason} with a bound of {} and a gap of {}",
+						solution.bound,
+						solution.gap()
+					)?;
+				}
+
+				if solution.status == SolutionStatus::Unbounded {
+					writeln!(writer, "The objective is unbounded")?;
+				} else if !solution.objective.is_finite() {
+					writeln!(writer, "No integer solution found")?;
+				} else {
+					writeln!(
+						writer,
+						"Best integer solution from problem {} with objective value: {}",
+						solution.section, solution.objective
+					)?;
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: Number, W: Write> SolverObserver<T> for TextLog<W> {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		if self.error.is_none()
+			&& let Err(error) = self.write(event)
+		{
+			self.error = Some(error);
+		}
+	}
+}
@@ -0,0 +1,108 @@
+use crate::{Branching, CancelToken, NodeSelection};
+use std::time::Duration;
+
+/// Settings for the simplex and [`crate::branch_and_bound`].
+#[derive(Clone, Debug)]
+pub struct SolverOptions {
+	/// Nodes whose bound is not better than the incumbent by more than this amount are pruned.
+	pub abs_gap: f32,
+	/// Like `abs_gap`, but relative to the magnitude of the incumbent objective value.
+	pub rel_gap: f32,
+	/// Order in which open nodes are processed.
+	pub node_selection: NodeSelection,
+	/// Rule used to pick the variable a node is branched on.
+	pub branching: Branching,
+	/// Rule the simplex picks its pivots by.
+	pub pivot_rule: PivotRule,
+	/// Pivots a single simplex run may perform before giving up with
+	/// [`crate::SolveError::IterationLimit`].
+	pub pivot_limit: usize,
+	/// Nodes branch and bound may open before stopping with
+	/// [`crate::SolutionStatus::LimitReached`].
+	pub node_limit: Option<usize>,
+	/// Time branch and bound may run before stopping with [`crate::SolutionStatus::LimitReached`].
+	/// It is checked between nodes, so the relaxation being solved still runs to the end or to
+	/// `pivot_limit`.
+	pub time_limit: Option<Duration>,
+	/// Stops the solve once cancelled, keep a clone to cancel it from another thread.
+	pub cancel: CancelToken,
+	/// Threads branch and bound solves relaxations on, with one everything runs on the calling
+	/// thread.
+	pub threads: usize,
+	/// Makes a search on several threads open its nodes in the same order and find the same
+	/// solution as one on a single thread, only solving the two children of a node side by side.
+	/// Otherwise up to `threads` nodes are processed at once, which is faster but may open nodes
+	/// in a different order from one run to the next.
+	pub deterministic: bool,
+	pub tolerances: Tolerances,
+}
+
+impl Default for SolverOptions {
+	fn default() -> Self {
+		SolverOptions {
+			abs_gap: 1e-6,
+			rel_gap: 0.0,
+			node_selection: NodeSelection::default(),
+			branching: Branching::default(),
+			pivot_rule: PivotRule::default(),
+			pivot_limit: 10_000,
+			node_limit: None,
+			time_limit: None,
+			cancel: CancelToken::new(),
+			threads: 1,
+			deterministic: false,
+			tolerances: Tolerances::default(),
+		}
+	}
+}
+
+/// How the simplex picks the entering and leaving variable of a pivot.
+///
+/// Degenerate tableaux, which knapsacks become once branching fixes variables at their bounds, can
+/// pivot between the same bases forever without improving the objective. Every rule but
+/// [`PivotRule::Bland`] watches for a basis coming back and switches to Bland's rule for the rest
+/// of the run when it does.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PivotRule {
+	/// The most negative objective coefficient enters and ties in the ratio test go to the first
+	/// row, which usually takes the fewest pivots.
+	#[default]
+	Dantzig,
+	/// The first column with a negative objective coefficient enters and ties in the ratio test go
+	/// to the row with the lowest basic column, which can't cycle but tends to take more pivots. The
+	/// dual simplex likewise takes the infeasible row with the lowest basic column.
+	Bland,
+	/// Like [`PivotRule::Dantzig`], but ties in the ratio test go to the row that is
+	/// lexicographically smallest once divided by the entering coefficient, which breaks the ties
+	/// degenerate tableaux are full of without giving up Dantzig's choice of entering variable.
+	Lexicographic,
+}
+
+/// How far values may be off before the simplex and branch and bound stop treating them as exact,
+/// floats leave rounding noise such as 0.99999994 or 1.4901161e-8 behind every pivot. Exact number
+/// types ignore these, see [`crate::Number::tolerance`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerances {
+	/// Basic variables may lie this far outside their bounds, and phase one objectives this far
+	/// below zero, while still counting as feasible.
+	pub feasibility: f32,
+	/// Objective coefficients this far below zero still count as optimal, and coefficients this
+	/// close to zero mark basic columns.
+	pub optimality: f32,
+	/// Values this close to an integer count as integral.
+	pub integrality: f32,
+	/// Coefficients this small count as zero, so they are never pivoted on and don't limit ratio
+	/// tests.
+	pub pivot: f32,
+}
+
+impl Default for Tolerances {
+	fn default() -> Self {
+		Tolerances {
+			feasibility: 1e-5,
+			optimality: 1e-6,
+			integrality: 1e-5,
+			pivot: 1e-6,
+		}
+	}
+}
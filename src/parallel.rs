@@ -0,0 +1,367 @@
+use crate::{
+	Branch, BranchDirection, Candidate, Number, Problem, Search, Snapshot, SolveError, SolverEvent,
+	SolverObserver, VariableInfo, create_branch,
+};
+use std::{
+	any::Any,
+	collections::HashMap,
+	panic::{self, AssertUnwindSafe},
+	sync::{Arc, Mutex, mpsc},
+	thread,
+	time::Duration,
+};
+
+/// Relaxation a worker thread solves.
+enum Job<T: Number> {
+	Rebuild {
+		section: String,
+		snapshot: Snapshot<T>,
+	},
+	Child {
+		node: usize,
+		parent: Arc<Problem<T>>,
+		candidate: Candidate<T>,
+		direction: BranchDirection,
+	},
+}
+
+enum Done<T: Number> {
+	Rebuilt {
+		section: String,
+		bound: T,
+		result: Result<Problem<T>, SolveError>,
+	},
+	Child {
+		node: usize,
+		direction: BranchDirection,
+		result: Result<Problem<T>, SolveError>,
+		events: Recorder<T>,
+	},
+	/// A worker panicked, the search panics with it.
+	Panicked(Box<dyn Any + Send>),
+}
+
+/// A solved child with the events solving it reported.
+type SolvedChild<T> = (Result<Problem<T>, SolveError>, Recorder<T>);
+
+/// A node whose children are being solved.
+struct Pending<T: Number> {
+	branch: Branch<T>,
+	down: Option<SolvedChild<T>>,
+	up: Option<SolvedChild<T>>,
+}
+
+/// Keeps the events of a child solved on a worker thread, so the coordinator can report them in the
+/// order the sequential search would.
+struct Recorder<T> {
+	events: Vec<Recorded<T>>,
+}
+
+enum Recorded<T> {
+	Pivot {
+		column: usize,
+		row: usize,
+		iterations: usize,
+		dual: bool,
+	},
+	Solved {
+		objective: T,
+		values: Vec<T>,
+		iterations: usize,
+		time: Duration,
+	},
+	Failed {
+		error: SolveError,
+		time: Duration,
+	},
+}
+
+impl<T: Number> SolverObserver<T> for Recorder<T> {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		let recorded = match *event {
+			SolverEvent::PivotPerformed {
+				column,
+				row,
+				iterations,
+				dual,
+				..
+			} => Recorded::Pivot {
+				column,
+				row,
+				iterations,
+				dual,
+			},
+			SolverEvent::LpSolved {
+				objective,
+				values,
+				iterations,
+				time,
+				..
+			} => Recorded::Solved {
+				objective,
+				values: values.to_vec(),
+				iterations,
+				time,
+			},
+			SolverEvent::LpFailed { error, time, .. } => Recorded::Failed { error, time },
+			// solving a child reports nothing else
+			_ => return,
+		};
+
+		self.events.push(recorded);
+	}
+}
+
+impl<T: Number> Recorder<T> {
+	fn replay(self, section: &str, variables: &[VariableInfo], observer: &mut dyn SolverObserver<T>) {
+		for recorded in self.events {
+			match recorded {
+				Recorded::Pivot {
+					column,
+					row,
+					iterations,
+					dual,
+				} => observer.on_event(&SolverEvent::PivotPerformed {
+					column,
+					row,
+					variable: &variables[column].name,
+					iterations,
+					dual,
+				}),
+				Recorded::Solved {
+					objective,
+					values,
+					iterations,
+					time,
+				} => observer.on_event(&SolverEvent::LpSolved {
+					section,
+					objective,
+					values: &values,
+					variables,
+					iterations,
+					time,
+				}),
+				Recorded::Failed { error, time } => observer.on_event(&SolverEvent::LpFailed {
+					section,
+					error,
+					time,
+				}),
+			}
+		}
+	}
+}
+
+fn run<T: Number>(root: &Problem<T>, job: Job<T>) -> Done<T> {
+	match job {
+		Job::Rebuild { section, snapshot } => {
+			let bound = snapshot.bound;
+			let result = snapshot.rebuild(root, section.clone());
+			Done::Rebuilt {
+				section,
+				bound,
+				result,
+			}
+		}
+		Job::Child {
+			node,
+			parent,
+			candidate,
+			direction,
+		} => {
+			let mut events = Recorder { events: Vec::new() };
+			let result = create_branch(&parent, &candidate, direction, &mut events);
+			Done::Child {
+				node,
+				direction,
+				result,
+				events,
+			}
+		}
+	}
+}
+
+/// Runs the search with node rebuilds and child relaxations solved on `options.threads` worker
+/// threads. The queue, branching rule, incumbent and observer stay on the calling thread, which
+/// hands out the work and settles the results in the order of the sequential search.
+pub(crate) fn search<T: Number>(search: &mut Search<'_, T>) {
+	let threads = search.options.threads;
+	// with one node at a time nodes open in the sequential order, only the children are solved
+	// side by side
+	let capacity = if search.options.deterministic {
+		1
+	} else {
+		threads
+	};
+
+	let (job_sender, jobs) = mpsc::channel::<Job<T>>();
+	let jobs = Mutex::new(jobs);
+	let (done_sender, done) = mpsc::channel::<Done<T>>();
+	let root = Arc::clone(&search.root);
+
+	thread::scope(|scope| {
+		for _ in 0..threads {
+			let jobs = &jobs;
+			let root = &root;
+			let done_sender = done_sender.clone();
+			scope.spawn(move || {
+				loop {
+					// the lock is only held while waiting, the sender going away ends the worker
+					let Ok(job) = jobs
+						.lock()
+						.expect("workers don't panic holding the lock")
+						.recv()
+					else {
+						break;
+					};
+
+					let done =
+						panic::catch_unwind(AssertUnwindSafe(|| run(root, job))).unwrap_or_else(Done::Panicked);
+					if done_sender.send(done).is_err() {
+						break;
+					}
+				}
+			});
+		}
+
+		let mut pending = HashMap::new();
+		let mut next_node = 0;
+		let mut in_flight = 0;
+		loop {
+			while in_flight < capacity {
+				let Some((section, snapshot)) = search.next() else {
+					break;
+				};
+
+				job_sender
+					.send(Job::Rebuild { section, snapshot })
+					.expect("workers run until the search ends");
+				in_flight += 1;
+			}
+
+			if in_flight == 0 {
+				break;
+			}
+
+			match done.recv().expect("workers run until the search ends") {
+				Done::Rebuilt {
+					section,
+					bound,
+					result,
+				} => {
+					let Some(branch) = search.branch(&section, bound, result) else {
+						in_flight -= 1;
+						continue;
+					};
+
+					next_node += 1;
+					for direction in [BranchDirection::Down, BranchDirection::Up] {
+						job_sender
+							.send(Job::Child {
+								node: next_node,
+								parent: Arc::clone(&branch.problem),
+								candidate: branch.candidate,
+								direction,
+							})
+							.expect("workers run until the search ends");
+					}
+
+					pending.insert(
+						next_node,
+						Pending {
+							branch,
+							down: None,
+							up: None,
+						},
+					);
+				}
+				Done::Child {
+					node,
+					direction,
+					result,
+					events,
+				} => {
+					let entry = pending
+						.get_mut(&node)
+						.expect("children belong to a pending node");
+					match direction {
+						BranchDirection::Down => entry.down = Some((result, events)),
+						BranchDirection::Up => entry.up = Some((result, events)),
+					}
+
+					if entry.down.is_none() || entry.up.is_none() {
+						continue;
+					}
+
+					let Pending {
+						mut branch,
+						down: Some(down),
+						up: Some(up),
+					} = pending.remove(&node).expect("the node was just found")
+					else {
+						unreachable!("both children were just checked");
+					};
+
+					for (direction, (result, events)) in
+						[(BranchDirection::Down, down), (BranchDirection::Up, up)]
+					{
+						let section = search.announce(&branch, direction);
+						events.replay(
+							&section,
+							&branch.problem.tableau.variable_map,
+							&mut *search.observer,
+						);
+						search.settle(&mut branch, direction, &section, result);
+					}
+
+					search.close(branch);
+					in_flight -= 1;
+				}
+				Done::Panicked(payload) => panic::resume_unwind(payload),
+			}
+		}
+
+		// dropping the sender stops the workers, which the scope waits for
+		drop(job_sender);
+	});
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Knapsack, KnapsackMethod, SolverOptions};
+
+	fn knapsack(threads: usize, deterministic: bool) -> Knapsack {
+		// xorshift, so the items are the same on every run
+		let mut state = 0x2545_f491_u64;
+		let mut next = |low: u64, high: u64| {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			(low + state % (high - low + 1)) as f32
+		};
+
+		let (values, weights): (Vec<_>, Vec<_>) = (0..18).map(|_| (next(5, 60), next(10, 50))).unzip();
+		let capacity = weights.iter().sum::<f32>() / 3.0;
+		Knapsack::new(values, weights, capacity)
+			.with_method(KnapsackMethod::BranchAndBound)
+			.with_options(SolverOptions {
+				threads,
+				deterministic,
+				..Default::default()
+			})
+	}
+
+	fn log(knapsack: &Knapsack) -> String {
+		let mut log = Vec::new();
+		knapsack.solve_with_log(&mut log).unwrap();
+		String::from_utf8(log).unwrap()
+	}
+
+	#[test]
+	fn deterministic_log_matches_sequential() {
+		let sequential = log(&knapsack(1, false));
+		assert!(sequential.matches("=== Processing Problem").count() > 100);
+		for _ in 0..5 {
+			assert_eq!(log(&knapsack(4, true)), sequential);
+		}
+	}
+}
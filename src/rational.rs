@@ -0,0 +1,509 @@
+use crate::Number;
+use matrix::Element;
+use std::{
+	cmp::Ordering,
+	fmt,
+	ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// Exact fraction of two `i128`s, for solving without rounding errors at the cost of speed.
+///
+/// Fractions are kept in lowest terms with a positive denominator. Like floats, dividing by zero
+/// gives an infinity, or NaN for `0 / 0`, so that unbounded variables can be represented.
+///
+/// # Panics
+///
+/// Arithmetic panics if a numerator or denominator no longer fits an `i128`, rather than silently
+/// losing the exactness the type is for.
+#[derive(Clone, Copy, Debug)]
+pub struct Rational {
+	numer: i128,
+	/// Zero for infinities and NaN, whose numerator is then the sign or zero respectively.
+	denom: i128,
+}
+
+impl Rational {
+	/// # Panics
+	///
+	/// Panics if `denom` is zero.
+	pub fn new(numer: i128, denom: i128) -> Self {
+		assert!(denom != 0, "rationals need a non-zero denominator");
+		Rational::reduced(numer, denom)
+	}
+
+	pub fn numer(self) -> i128 {
+		self.numer
+	}
+
+	pub fn denom(self) -> i128 {
+		self.denom
+	}
+
+	pub fn is_nan(self) -> bool {
+		self.denom == 0 && self.numer == 0
+	}
+
+	/// `numer / denom` in lowest terms, a zero denominator gives an infinity or NaN.
+	fn reduced(numer: i128, denom: i128) -> Self {
+		if denom == 0 {
+			return Rational {
+				numer: numer.signum(),
+				denom: 0,
+			};
+		}
+
+		let divisor = gcd(numer.unsigned_abs(), denom.unsigned_abs());
+		let (numer, denom) = if denom < 0 {
+			(checked(numer.checked_neg()), checked(denom.checked_neg()))
+		} else {
+			(numer, denom)
+		};
+
+		// the divisor divides the now positive denominator, so it fits an i128
+		let divisor = divisor as i128;
+		Rational {
+			numer: numer / divisor,
+			denom: denom / divisor,
+		}
+	}
+
+	fn nan() -> Self {
+		Rational { numer: 0, denom: 0 }
+	}
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+	while b != 0 {
+		(a, b) = (b, a % b);
+	}
+
+	a
+}
+
+/// Unwraps the result of checked integer arithmetic.
+fn checked(value: Option<i128>) -> i128 {
+	value.expect("rational arithmetic overflowed i128")
+}
+
+/// Orders `a / b` and `c / d`, for positive `b` and `d`, by comparing their continued fractions,
+/// which unlike cross multiplying can't overflow.
+fn compare(mut a: i128, mut b: i128, mut c: i128, mut d: i128) -> Ordering {
+	loop {
+		let (q1, r1) = (a.div_euclid(b), a.rem_euclid(b));
+		let (q2, r2) = (c.div_euclid(d), c.rem_euclid(d));
+		if q1 != q2 {
+			return q1.cmp(&q2);
+		}
+
+		match (r1 == 0, r2 == 0) {
+			(true, true) => return Ordering::Equal,
+			(true, false) => return Ordering::Less,
+			(false, true) => return Ordering::Greater,
+			// r1 / b < r2 / d exactly when d / r2 < b / r1
+			(false, false) => (a, b, c, d) = (d, r2, b, r1),
+		}
+	}
+}
+
+impl From<i64> for Rational {
+	fn from(value: i64) -> Self {
+		Rational {
+			numer: value as i128,
+			denom: 1,
+		}
+	}
+}
+
+impl From<i32> for Rational {
+	fn from(value: i32) -> Self {
+		Rational::from(value as i64)
+	}
+}
+
+impl Default for Rational {
+	fn default() -> Self {
+		Rational::zero()
+	}
+}
+
+impl PartialEq for Rational {
+	fn eq(&self, other: &Self) -> bool {
+		!self.is_nan() && self.numer == other.numer && self.denom == other.denom
+	}
+}
+
+impl PartialOrd for Rational {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		if self.is_nan() || other.is_nan() {
+			return None;
+		}
+
+		match (self.denom, other.denom) {
+			(0, 0) => Some(self.numer.cmp(&other.numer)),
+			(0, _) => Some(self.numer.cmp(&0)),
+			(_, 0) => Some(0.cmp(&other.numer)),
+			_ => Some(compare(self.numer, self.denom, other.numer, other.denom)),
+		}
+	}
+}
+
+impl Neg for Rational {
+	type Output = Rational;
+
+	fn neg(self) -> Rational {
+		Rational {
+			numer: checked(self.numer.checked_neg()),
+			denom: self.denom,
+		}
+	}
+}
+
+impl Add for Rational {
+	type Output = Rational;
+
+	fn add(self, other: Rational) -> Rational {
+		match (self.denom, other.denom) {
+			// opposite infinities and NaN give NaN
+			(0, 0) if self.numer != other.numer => Rational::nan(),
+			(0, _) => self,
+			(_, 0) => other,
+			_ => {
+				let divisor = gcd(self.denom as u128, other.denom as u128) as i128;
+				let left = checked(self.numer.checked_mul(other.denom / divisor));
+				let right = checked(other.numer.checked_mul(self.denom / divisor));
+				Rational::reduced(
+					checked(left.checked_add(right)),
+					checked(self.denom.checked_mul(other.denom / divisor)),
+				)
+			}
+		}
+	}
+}
+
+impl Sub for Rational {
+	type Output = Rational;
+
+	fn sub(self, other: Rational) -> Rational {
+		self + -other
+	}
+}
+
+impl Mul for Rational {
+	type Output = Rational;
+
+	fn mul(self, other: Rational) -> Rational {
+		if self.denom == 0 || other.denom == 0 {
+			// infinity times zero is NaN, which the signum product covers
+			return Rational {
+				numer: self.numer.signum() * other.numer.signum(),
+				denom: 0,
+			};
+		}
+
+		// reducing crosswise first keeps the products small
+		let left = gcd(self.numer.unsigned_abs(), other.denom as u128) as i128;
+		let right = gcd(other.numer.unsigned_abs(), self.denom as u128) as i128;
+		Rational::reduced(
+			checked((self.numer / left).checked_mul(other.numer / right)),
+			checked((self.denom / right).checked_mul(other.denom / left)),
+		)
+	}
+}
+
+impl Div for Rational {
+	type Output = Rational;
+
+	fn div(self, other: Rational) -> Rational {
+		match (self.denom, other.denom) {
+			(0, 0) => Rational::nan(),
+			(_, 0) if !other.is_nan() => Rational::zero(),
+			(_, 0) => Rational::nan(),
+			_ if other.numer == 0 => Rational {
+				numer: self.numer.signum(),
+				denom: 0,
+			},
+			_ => self * Rational::reduced(other.denom, other.numer),
+		}
+	}
+}
+
+impl AddAssign for Rational {
+	fn add_assign(&mut self, other: Rational) {
+		*self = *self + other;
+	}
+}
+
+impl SubAssign for Rational {
+	fn sub_assign(&mut self, other: Rational) {
+		*self = *self - other;
+	}
+}
+
+impl MulAssign for Rational {
+	fn mul_assign(&mut self, other: Rational) {
+		*self = *self * other;
+	}
+}
+
+/// Prints `numer/denom`, or an approximate decimal when a precision is given, e.g. `{:.3}`.
+impl fmt::Display for Rational {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let text = match (self.denom, self.numer) {
+			(0, 0) => "NaN".to_string(),
+			(0, 1) => "inf".to_string(),
+			(0, _) => "-inf".to_string(),
+			_ => match f.precision() {
+				Some(precision) => format!("{:.*}", precision, self.to_f64()),
+				None if self.denom == 1 => self.numer.to_string(),
+				None => format!("{}/{}", self.numer, self.denom),
+			},
+		};
+
+		match f.width() {
+			Some(width) => write!(f, "{text:>width$}"),
+			None => f.write_str(&text),
+		}
+	}
+}
+
+impl Element for Rational {
+	fn zero() -> Self {
+		Rational { numer: 0, denom: 1 }
+	}
+}
+
+impl Number for Rational {
+	const EXACT: bool = true;
+
+	fn one() -> Self {
+		Rational { numer: 1, denom: 1 }
+	}
+
+	fn infinity() -> Self {
+		Rational { numer: 1, denom: 0 }
+	}
+
+	/// Exact, every finite `f32` is a fraction with a power of two as its denominator. Values too
+	/// large for an `i128` become infinite and values too small become zero.
+	fn from_f32(value: f32) -> Self {
+		if value.is_nan() {
+			return Rational::nan();
+		}
+
+		if value.is_infinite() {
+			return Rational {
+				numer: value.signum() as i128,
+				denom: 0,
+			};
+		}
+
+		let bits = value.to_bits();
+		let sign = if bits >> 31 == 1 { -1 } else { 1 };
+		let biased = ((bits >> 23) & 0xff) as i32;
+		let fraction = (bits & 0x7f_ffff) as i128;
+		// value = mantissa * 2^exponent, subnormals have no implicit leading one
+		let (mantissa, exponent) = if biased == 0 {
+			(fraction, -149)
+		} else {
+			(fraction | 1 << 23, biased - 150)
+		};
+
+		if mantissa == 0 {
+			return Rational::zero();
+		}
+
+		let zeros = mantissa.trailing_zeros() as i32;
+		let (mantissa, exponent) = (mantissa >> zeros, exponent + zeros);
+		if exponent >= 0 {
+			// the mantissa has at most 24 bits
+			if exponent > 102 {
+				return Rational {
+					numer: sign,
+					denom: 0,
+				};
+			}
+
+			Rational {
+				numer: sign * (mantissa << exponent),
+				denom: 1,
+			}
+		} else if -exponent > 126 {
+			Rational::zero()
+		} else {
+			Rational {
+				numer: sign * mantissa,
+				denom: 1 << -exponent,
+			}
+		}
+	}
+
+	fn to_f64(self) -> f64 {
+		self.numer as f64 / self.denom as f64
+	}
+
+	fn abs(self) -> Self {
+		if self.numer < 0 { -self } else { self }
+	}
+
+	fn floor(self) -> Self {
+		if self.denom == 0 {
+			return self;
+		}
+
+		Rational {
+			numer: self.numer.div_euclid(self.denom),
+			denom: 1,
+		}
+	}
+
+	fn ceil(self) -> Self {
+		-(-self).floor()
+	}
+
+	/// Rounds half-way cases away from zero, like the float types.
+	fn round(self) -> Self {
+		let half = Rational { numer: 1, denom: 2 };
+		if self.numer < 0 {
+			-(-self + half).floor()
+		} else {
+			(self + half).floor()
+		}
+	}
+
+	fn is_finite(self) -> bool {
+		self.denom != 0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn r(numer: i128, denom: i128) -> Rational {
+		Rational::new(numer, denom)
+	}
+
+	#[test]
+	fn new_reduces_to_lowest_terms() {
+		let value = r(6, -4);
+		assert_eq!((value.numer(), value.denom()), (-3, 2));
+	}
+
+	#[test]
+	fn compare_orders_fractions() {
+		assert!(r(1, 3) < r(1, 2));
+		assert!(r(-1, 3) > r(-1, 2));
+		assert!(r(-7, 2) < r(-3, 1));
+		assert_eq!(r(2, 4).partial_cmp(&r(1, 2)), Some(Ordering::Equal));
+		assert_eq!(compare(5, 3, 5, 3), Ordering::Equal);
+		assert_eq!(compare(4, 3, 5, 4), Ordering::Greater);
+	}
+
+	#[test]
+	fn compare_does_not_overflow() {
+		// cross multiplying these would overflow an i128
+		let max = i128::MAX;
+		assert!(r(max, max - 1) < r(max - 1, max - 2));
+		assert!(r(-max, max - 1) > r(-(max - 1), max - 2));
+	}
+
+	#[test]
+	fn infinities_order_around_finite_values() {
+		let inf = Rational::infinity();
+		assert!(-inf < r(-1_000_000, 1));
+		assert!(r(1_000_000, 1) < inf);
+		assert!(-inf < inf);
+		assert_eq!(inf, inf);
+	}
+
+	#[test]
+	fn infinite_arithmetic() {
+		let inf = Rational::infinity();
+		assert_eq!(inf + r(1, 1), inf);
+		assert_eq!(r(1, 1) - inf, -inf);
+		assert_eq!(inf * r(-2, 1), -inf);
+		assert_eq!(r(3, 1) / inf, Rational::zero());
+		assert_eq!(r(1, 1) / Rational::zero(), inf);
+		assert_eq!(r(-1, 1) / Rational::zero(), -inf);
+		assert!(!inf.is_finite());
+		assert_eq!(inf.floor(), inf);
+	}
+
+	#[test]
+	fn nan_arithmetic() {
+		let inf = Rational::infinity();
+		assert!((inf - inf).is_nan());
+		assert!((inf * Rational::zero()).is_nan());
+		assert!((Rational::zero() / Rational::zero()).is_nan());
+		assert!((inf / inf).is_nan());
+
+		let nan = Rational::zero() / Rational::zero();
+		assert_ne!(nan, nan);
+		assert_eq!(nan.partial_cmp(&r(1, 1)), None);
+		assert!((nan + r(1, 1)).is_nan());
+	}
+
+	#[test]
+	fn finite_arithmetic() {
+		assert_eq!(r(1, 3) + r(1, 6), r(1, 2));
+		assert_eq!(r(1, 3) - r(1, 2), r(-1, 6));
+		assert_eq!(r(2, 3) * r(9, 4), r(3, 2));
+		assert_eq!(r(2, 3) / r(-4, 9), r(-3, 2));
+	}
+
+	#[test]
+	#[should_panic(expected = "overflowed")]
+	fn overflow_panics() {
+		let _ = r(i128::MAX, 1) + r(1, 1);
+	}
+
+	#[test]
+	fn from_f32_is_exact() {
+		assert_eq!(Rational::from_f32(-0.375), r(-3, 8));
+		assert_eq!(Rational::from_f32(0.0), Rational::zero());
+		assert_eq!(Rational::from_f32(-0.0), Rational::zero());
+		assert_eq!(Rational::from_f32(0.1).to_f64(), 0.1f32 as f64);
+	}
+
+	#[test]
+	fn from_f32_large_values() {
+		assert_eq!(Rational::from_f32(2f32.powi(100)), r(1 << 100, 1));
+		assert_eq!(Rational::from_f32(-2f32.powi(102)), r(-(1 << 102), 1));
+		// past the range of an i128
+		assert_eq!(Rational::from_f32(f32::MAX), Rational::infinity());
+		assert_eq!(Rational::from_f32(f32::MIN), -Rational::infinity());
+		assert_eq!(Rational::from_f32(f32::INFINITY), Rational::infinity());
+		assert!(Rational::from_f32(f32::NAN).is_nan());
+	}
+
+	#[test]
+	fn from_f32_small_values() {
+		assert_eq!(Rational::from_f32(f32::MIN_POSITIVE), r(1, 1 << 126));
+		// subnormals have denominators past the range of an i128
+		assert_eq!(Rational::from_f32(f32::from_bits(1)), Rational::zero());
+		assert_eq!(
+			Rational::from_f32(-f32::MIN_POSITIVE / 2.0),
+			Rational::zero()
+		);
+	}
+
+	#[test]
+	fn floor_ceil_and_round_negatives() {
+		assert_eq!(r(-7, 2).floor(), r(-4, 1));
+		assert_eq!(r(-7, 2).ceil(), r(-3, 1));
+		assert_eq!(r(-7, 2).round(), r(-4, 1));
+		assert_eq!(r(-5, 4).round(), r(-1, 1));
+		assert_eq!(r(-7, 4).round(), r(-2, 1));
+		assert_eq!(r(-3, 1).floor(), r(-3, 1));
+		assert_eq!(r(7, 2).round(), r(4, 1));
+		assert_eq!(r(7, 2).floor(), r(3, 1));
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(r(-3, 2).to_string(), "-3/2");
+		assert_eq!(r(4, 2).to_string(), "2");
+		assert_eq!(format!("{:.2}", r(1, 3)), "0.33");
+		assert_eq!(Rational::infinity().to_string(), "inf");
+	}
+}
@@ -0,0 +1,320 @@
+use crate::{Number, Problem};
+use std::{
+	cmp::Ordering,
+	collections::{BinaryHeap, VecDeque},
+	fmt,
+	sync::Arc,
+};
+
+/// An open node waiting to be processed by [`crate::branch_and_bound`]. The node only carries what
+/// selectors need, the search keeps its bound changes and optimal basis to rebuild the tableau from
+/// once the node is processed.
+pub struct Node {
+	id: usize,
+	section: String,
+	bound: f32,
+	depth: usize,
+	estimate: f32,
+}
+
+impl Node {
+	pub(crate) fn new<T: Number>(id: usize, problem: &Problem<T>) -> Self {
+		let depth = problem.section.matches('.').count();
+		// every fractional integer variable is assumed to cost one unit of objective per unit it has
+		// to move to reach the nearest integer, which is crude but cheap and keeps the estimate below
+		// the bound
+		let degradation = problem
+			.values()
+			.into_iter()
+			.zip(&problem.tableau.variable_map)
+			.filter(|(_, variable)| variable.integer)
+			.map(|(v, _)| {
+				let frac = (v - v.floor()).to_f32();
+				frac.min(1.0 - frac)
+			})
+			.sum::<f32>();
+
+		Node {
+			id,
+			section: problem.section.clone(),
+			bound: problem.objective().to_f32(),
+			depth,
+			estimate: problem.objective().to_f32() - degradation,
+		}
+	}
+
+	pub(crate) fn id(&self) -> usize {
+		self.id
+	}
+
+	/// Objective value of the node's relaxation, an upper bound on any integer solution below it.
+	/// Like every value handed to selectors and branching rules it is in maximisation form, so
+	/// higher is always better regardless of the problem's sense.
+	pub fn bound(&self) -> f32 {
+		self.bound
+	}
+
+	/// Estimated objective value of the best integer solution below the node.
+	pub fn estimate(&self) -> f32 {
+		self.estimate
+	}
+
+	pub fn depth(&self) -> usize {
+		self.depth
+	}
+
+	pub fn section(&self) -> &str {
+		&self.section
+	}
+}
+
+/// Decides the order in which open nodes are processed.
+pub trait NodeSelector {
+	fn push(&mut self, node: Node);
+
+	fn pop(&mut self) -> Option<Node>;
+
+	fn len(&self) -> usize;
+
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Called whenever the incumbent improves.
+	fn on_incumbent(&mut self, _objective: f32) {}
+}
+
+/// Processes nodes in the order they were created.
+#[derive(Default)]
+pub struct BreadthFirst {
+	queue: VecDeque<Node>,
+}
+
+impl NodeSelector for BreadthFirst {
+	fn push(&mut self, node: Node) {
+		self.queue.push_back(node);
+	}
+
+	fn pop(&mut self) -> Option<Node> {
+		self.queue.pop_front()
+	}
+
+	fn len(&self) -> usize {
+		self.queue.len()
+	}
+}
+
+/// Processes the most recently created node first, reaching integer solutions quickly.
+#[derive(Default)]
+pub struct DepthFirst {
+	stack: Vec<Node>,
+}
+
+impl NodeSelector for DepthFirst {
+	fn push(&mut self, node: Node) {
+		self.stack.push(node);
+	}
+
+	fn pop(&mut self) -> Option<Node> {
+		self.stack.pop()
+	}
+
+	fn len(&self) -> usize {
+		self.stack.len()
+	}
+}
+
+struct Ranked {
+	key: f32,
+	order: usize,
+	node: Node,
+}
+
+impl PartialEq for Ranked {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Ranked {}
+
+impl PartialOrd for Ranked {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for Ranked {
+	fn cmp(&self, other: &Self) -> Ordering {
+		// ties go to the node that was created first
+		self
+			.key
+			.total_cmp(&other.key)
+			.then_with(|| other.order.cmp(&self.order))
+	}
+}
+
+/// Priority queue returning the node with the highest key first.
+struct RankedQueue {
+	heap: BinaryHeap<Ranked>,
+	order: usize,
+	key: fn(&Node) -> f32,
+}
+
+impl RankedQueue {
+	fn new(key: fn(&Node) -> f32) -> Self {
+		RankedQueue {
+			heap: BinaryHeap::new(),
+			order: 0,
+			key,
+		}
+	}
+
+	fn push(&mut self, node: Node) {
+		self.order += 1;
+		self.heap.push(Ranked {
+			key: (self.key)(&node),
+			order: self.order,
+			node,
+		});
+	}
+
+	fn pop(&mut self) -> Option<Node> {
+		self.heap.pop().map(|ranked| ranked.node)
+	}
+}
+
+/// Processes the node with the best relaxation bound first, which keeps the number of processed
+/// nodes low but finds incumbents late.
+pub struct BestBound {
+	queue: RankedQueue,
+}
+
+impl Default for BestBound {
+	fn default() -> Self {
+		BestBound {
+			queue: RankedQueue::new(Node::bound),
+		}
+	}
+}
+
+impl NodeSelector for BestBound {
+	fn push(&mut self, node: Node) {
+		self.queue.push(node);
+	}
+
+	fn pop(&mut self) -> Option<Node> {
+		self.queue.pop()
+	}
+
+	fn len(&self) -> usize {
+		self.queue.heap.len()
+	}
+}
+
+/// Processes the node with the best [`Node::estimate`] first.
+pub struct BestEstimate {
+	queue: RankedQueue,
+}
+
+impl Default for BestEstimate {
+	fn default() -> Self {
+		BestEstimate {
+			queue: RankedQueue::new(Node::estimate),
+		}
+	}
+}
+
+impl NodeSelector for BestEstimate {
+	fn push(&mut self, node: Node) {
+		self.queue.push(node);
+	}
+
+	fn pop(&mut self) -> Option<Node> {
+		self.queue.pop()
+	}
+
+	fn len(&self) -> usize {
+		self.queue.heap.len()
+	}
+}
+
+/// Dives depth-first until the first incumbent is found, then switches to best-bound.
+#[derive(Default)]
+pub struct Hybrid {
+	dive: DepthFirst,
+	best: BestBound,
+	diving_done: bool,
+}
+
+impl NodeSelector for Hybrid {
+	fn push(&mut self, node: Node) {
+		if self.diving_done {
+			self.best.push(node);
+		} else {
+			self.dive.push(node);
+		}
+	}
+
+	fn pop(&mut self) -> Option<Node> {
+		if self.diving_done {
+			self.best.pop()
+		} else {
+			self.dive.pop()
+		}
+	}
+
+	fn len(&self) -> usize {
+		self.dive.len() + self.best.len()
+	}
+
+	fn on_incumbent(&mut self, _objective: f32) {
+		if self.diving_done {
+			return;
+		}
+
+		self.diving_done = true;
+		for node in self.dive.stack.drain(..) {
+			self.best.push(node);
+		}
+	}
+}
+
+/// Node selection strategy used by [`crate::branch_and_bound`].
+#[derive(Clone, Default)]
+pub enum NodeSelection {
+	#[default]
+	BreadthFirst,
+	DepthFirst,
+	BestBound,
+	BestEstimate,
+	Hybrid,
+	/// Creates a user supplied selector for every solve.
+	Custom(Arc<dyn Fn() -> Box<dyn NodeSelector> + Send + Sync>),
+}
+
+impl NodeSelection {
+	pub(crate) fn build(&self) -> Box<dyn NodeSelector> {
+		match self {
+			NodeSelection::BreadthFirst => Box::new(BreadthFirst::default()),
+			NodeSelection::DepthFirst => Box::new(DepthFirst::default()),
+			NodeSelection::BestBound => Box::new(BestBound::default()),
+			NodeSelection::BestEstimate => Box::new(BestEstimate::default()),
+			NodeSelection::Hybrid => Box::new(Hybrid::default()),
+			NodeSelection::Custom(factory) => factory(),
+		}
+	}
+}
+
+impl fmt::Debug for NodeSelection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			NodeSelection::BreadthFirst => write!(f, "BreadthFirst"),
+			NodeSelection::DepthFirst => write!(f, "DepthFirst"),
+			NodeSelection::BestBound => write!(f, "BestBound"),
+			NodeSelection::BestEstimate => write!(f, "BestEstimate"),
+			NodeSelection::Hybrid => write!(f, "Hybrid"),
+			NodeSelection::Custom(_) => write!(f, "Custom"),
+		}
+	}
+}
@@ -0,0 +1,283 @@
+use crate::{Bounds, Number, Tolerances};
+use matrix::format::Compressed;
+
+/// How an optimal linear program responds to changes in its objective and right-hand sides, see
+/// [`crate::Model::sensitivity`]. Everything holds as long as the optimal basis stays the same,
+/// which the ranges tell.
+#[derive(Clone, Debug)]
+pub struct Sensitivity<T = f32> {
+	pub objective: T,
+	pub variables: Vec<VariableSensitivity<T>>,
+	pub constraints: Vec<ConstraintSensitivity<T>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct VariableSensitivity<T = f32> {
+	pub name: String,
+	pub value: T,
+	/// Change in the objective per unit the variable moves up from its value, zero for variables
+	/// strictly between their bounds.
+	pub reduced_cost: T,
+	/// Lowest and highest objective coefficient for which the solution stays optimal, may be
+	/// infinite.
+	pub objective_range: (T, T),
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstraintSensitivity<T = f32> {
+	pub name: String,
+	pub rhs: T,
+	/// Change in the objective per unit the right-hand side grows, also known as the dual value.
+	pub shadow_price: T,
+	/// Lowest and highest right-hand side for which `shadow_price` holds, may be infinite.
+	pub rhs_range: (T, T),
+}
+
+impl<T: Number> Sensitivity<T> {
+	pub fn variable(&self, name: &str) -> Option<&VariableSensitivity<T>> {
+		self.variables.iter().find(|v| v.name == name)
+	}
+
+	pub fn constraint(&self, name: &str) -> Option<&ConstraintSensitivity<T>> {
+		self.constraints.iter().find(|c| c.name == name)
+	}
+}
+
+impl<T: Number> ConstraintSensitivity<T> {
+	/// Change in the objective when the right-hand side grows by `delta`, `None` if that leaves
+	/// `rhs_range` and the problem has to be solved again.
+	pub fn objective_change(&self, delta: T) -> Option<T> {
+		let rhs = self.rhs + delta;
+		(self.rhs_range.0 <= rhs && rhs <= self.rhs_range.1).then(|| self.shadow_price * delta)
+	}
+}
+
+/// Change in the objective per unit increase of the variable behind every column, read off the
+/// objective row of an optimal tableau in maximisation form. The reduced cost of a row's slack
+/// column is minus the row's shadow price.
+pub fn get_reduced_costs<T: Number>(obj_coef: &Compressed<T>, bounds: &Bounds<T>) -> Vec<T> {
+	(0..bounds.len())
+		.map(|j| {
+			// the row holds the cost of raising the column, which lowers a flipped variable
+			let value = obj_coef.get((0, j));
+			if bounds.flipped[j] { value } else { -value }
+		})
+		.collect()
+}
+
+/// How far the objective coefficient of the variable behind the column can decrease and increase
+/// before the basis of an optimal tableau in maximisation form stops being optimal. `basis` holds
+/// the basic column of every row.
+pub fn get_objective_range<T: Number>(
+	con_coef: &Compressed<T>,
+	obj_coef: &Compressed<T>,
+	bounds: &Bounds<T>,
+	basis: &[Option<usize>],
+	column: usize,
+	tolerances: &Tolerances,
+) -> (T, T) {
+	let zero = T::tolerance(tolerances.pivot);
+	let mut decrease = T::infinity();
+	let mut increase = T::infinity();
+	match basis.iter().position(|&basic| basic == Some(column)) {
+		// a non-basic column stays out until its cost drops below zero, fixed columns never enter
+		None if bounds.range(column).is_zero() => {}
+		None => increase = obj_coef.get((0, column)).max(T::zero()),
+		// raising the coefficient of a basic column adds its row to the objective row, which
+		// has to keep every cost of a column that can move non-negative
+		Some(row) => {
+			for j in (0..con_coef.columns).filter(|&j| !basis.contains(&Some(j))) {
+				let coef = con_coef.get((row, j));
+				if bounds.range(j).is_zero() || coef.abs() <= zero {
+					continue;
+				}
+
+				// rounding can leave costs a little below zero
+				let cost = obj_coef.get((0, j)).max(T::zero());
+				if coef > T::zero() {
+					decrease = decrease.min(cost / coef);
+				} else {
+					increase = increase.min(cost / -coef);
+				}
+			}
+		}
+	}
+
+	// the column stands for the distance from the upper bound once flipped
+	if bounds.flipped[column] {
+		(increase, decrease)
+	} else {
+		(decrease, increase)
+	}
+}
+
+/// How far the right-hand side of a row can decrease and increase before the basis of an optimal
+/// tableau stops being feasible, with `slack` the slack column the row had before solving. Rows
+/// are in the orientation they had then too, the simplex may have negated them since.
+pub fn get_rhs_range<T: Number>(
+	con_coef: &Compressed<T>,
+	con_rhs_coef: &Compressed<T>,
+	bounds: &Bounds<T>,
+	basis: &[Option<usize>],
+	slack: usize,
+	tolerances: &Tolerances,
+) -> (T, T) {
+	// a basic slack just takes up the change within its own bounds
+	if let Some(row) = basis.iter().position(|&basic| basic == Some(slack)) {
+		let value = bounds.value(slack, con_rhs_coef.get((row, 0)));
+		return (
+			(value - bounds.lower[slack]).max(T::zero()),
+			(bounds.upper[slack] - value).max(T::zero()),
+		);
+	}
+
+	// growing the right-hand side by delta is the same as the non-basic slack moving by -delta,
+	// which moves every basic column along the slack's column
+	let zero = T::tolerance(tolerances.pivot);
+	let mut decrease = T::infinity();
+	let mut increase = T::infinity();
+	for (i, &basic) in basis.iter().enumerate() {
+		let Some(basic) = basic else {
+			continue;
+		};
+
+		let rate = if bounds.flipped[slack] {
+			-con_coef.get((i, slack))
+		} else {
+			con_coef.get((i, slack))
+		};
+		if rate.abs() <= zero {
+			continue;
+		}
+
+		let value = con_rhs_coef.get((i, 0));
+		let room = bounds.range(basic) - value;
+		if rate > T::zero() {
+			decrease = decrease.min(value / rate);
+			if room.is_finite() {
+				increase = increase.min(room / rate);
+			}
+		} else {
+			increase = increase.min(value / -rate);
+			if room.is_finite() {
+				decrease = decrease.min(room / -rate);
+			}
+		}
+	}
+
+	// rounding can leave basic columns a little outside their bounds
+	(decrease.max(T::zero()), increase.max(T::zero()))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{Knapsack, Model, Number, Rational, Relation, Sense, SolveError};
+
+	#[test]
+	fn readme_knapsack() {
+		let knapsack = Knapsack::new(
+			vec![2.0, 3.0, 3.0, 5.0, 2.0, 4.0],
+			vec![11.0, 8.0, 6.0, 14.0, 10.0, 10.0],
+			40.0,
+		);
+		let sensitivity = knapsack.model().sensitivity().unwrap();
+		let capacity = sensitivity.constraint("capacity").unwrap();
+		assert_eq!(capacity.shadow_price, 0.2);
+		assert_eq!(capacity.rhs_range, (38.0, 48.0));
+		assert_eq!(capacity.objective_change(5.0), Some(1.0));
+		assert_eq!(capacity.objective_change(10.0), None);
+	}
+
+	#[test]
+	fn exact_knapsack_relaxation() {
+		let values = [2, 3, 3, 5, 2, 4];
+		let weights = [11, 8, 6, 14, 10, 10];
+		let mut model = Model::<Rational>::default();
+		let items = (0..values.len())
+			.map(|i| model.add_variable(&format!("x{}", i + 1), 0.into(), 1.into(), true))
+			.collect::<Vec<_>>();
+		let terms = |coefs: &[i64]| {
+			items
+				.iter()
+				.zip(coefs)
+				.map(|(&item, &coef)| (item, coef.into()))
+				.collect::<Vec<_>>()
+		};
+		model.add_constraint("capacity", &terms(&weights), Relation::LessEqual, 40.into());
+		model.set_objective(Sense::Maximise, &terms(&values));
+
+		let sensitivity = model.sensitivity().unwrap();
+		assert_eq!(sensitivity.objective, Rational::new(77, 5));
+
+		let capacity = sensitivity.constraint("capacity").unwrap();
+		assert_eq!(capacity.shadow_price, Rational::new(1, 5));
+		assert_eq!(capacity.rhs_range, (38.into(), 48.into()));
+
+		// x5 is the fractional item, x1 sits at zero and x2 at one
+		let x5 = sensitivity.variable("x5").unwrap();
+		assert_eq!(x5.value, Rational::new(1, 5));
+		assert_eq!(x5.reduced_cost, 0.into());
+		assert_eq!(
+			x5.objective_range,
+			(Rational::new(20, 11), Rational::new(25, 7))
+		);
+
+		let x1 = sensitivity.variable("x1").unwrap();
+		assert_eq!(x1.reduced_cost, Rational::new(-1, 5));
+		assert_eq!(
+			x1.objective_range,
+			(-Rational::infinity(), Rational::new(11, 5))
+		);
+
+		let x2 = sensitivity.variable("x2").unwrap();
+		assert_eq!(x2.reduced_cost, Rational::new(7, 5));
+		assert_eq!(
+			x2.objective_range,
+			(Rational::new(8, 5), Rational::infinity())
+		);
+	}
+
+	#[test]
+	fn minimising_with_greater_equal_row() {
+		// minimise 2x + 3y subject to x + y >= 4 and x <= 3, optimal at x = 3 and y = 1
+		let mut model = Model::<Rational>::default();
+		let x = model.add_variable("x", 0.into(), 3.into(), false);
+		let y = model.add_variable("y", 0.into(), Rational::infinity(), false);
+		model.add_constraint(
+			"demand",
+			&[(x, 1.into()), (y, 1.into())],
+			Relation::GreaterEqual,
+			4.into(),
+		);
+		model.set_objective(Sense::Minimise, &[(x, 2.into()), (y, 3.into())]);
+
+		let sensitivity = model.sensitivity().unwrap();
+		assert_eq!(sensitivity.objective, 9.into());
+
+		let demand = sensitivity.constraint("demand").unwrap();
+		assert_eq!(demand.shadow_price, 3.into());
+		assert_eq!(demand.rhs_range, (3.into(), Rational::infinity()));
+
+		let x = sensitivity.variable("x").unwrap();
+		assert_eq!(x.value, 3.into());
+		assert_eq!(x.reduced_cost, (-1).into());
+		assert_eq!(x.objective_range, (-Rational::infinity(), 3.into()));
+
+		let y = sensitivity.variable("y").unwrap();
+		assert_eq!(y.value, 1.into());
+		assert_eq!(y.objective_range, (2.into(), Rational::infinity()));
+	}
+
+	#[test]
+	fn unbounded_relaxation_is_an_error() {
+		let mut model = Model::<f64>::default();
+		let x = model.add_variable("x", 0.0, f64::INFINITY, false);
+		let y = model.add_variable("y", 0.0, f64::INFINITY, false);
+		model.add_constraint("c", &[(x, 1.0), (y, -1.0)], Relation::LessEqual, 3.0);
+		model.set_objective(Sense::Maximise, &[(x, 1.0)]);
+		assert!(matches!(
+			model.sensitivity(),
+			Err(SolveError::Unbounded { .. })
+		));
+	}
+}
@@ -0,0 +1,103 @@
+use crate::{Number, SolveError};
+
+/// How a branch and bound run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolutionStatus {
+	/// An integer solution was found and the tree was fully explored.
+	Optimal,
+	/// A solution was found by a heuristic that can't prove it optimal.
+	Feasible,
+	/// No node produced an integer solution.
+	Infeasible,
+	/// The relaxation's objective can grow without limit, so the objective and bound are infinite.
+	Unbounded,
+	/// The simplex failed on a relaxation for a reason other than infeasibility, so parts of the
+	/// tree could not be explored, see [`Solution::gap`].
+	Failed(SolveError),
+	/// The search stopped early at one of the limits in [`crate::SolverOptions`], the solution is
+	/// the best one found so far if there is one, see [`Solution::gap`].
+	LimitReached,
+	/// The search was cancelled through its [`crate::CancelToken`], the solution is the best one
+	/// found so far if there is one, see [`Solution::gap`].
+	Cancelled,
+}
+
+/// Why a node was closed without being branched on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PruneReason {
+	/// The node's relaxation bound could not improve on the incumbent.
+	Bound,
+	/// The node's relaxation has no feasible solution.
+	Infeasible,
+	/// The node's relaxation solution was already integral.
+	Integral,
+	/// The simplex failed on the node's relaxation for another reason.
+	Failed(SolveError),
+}
+
+impl From<SolveError> for PruneReason {
+	fn from(error: SolveError) -> Self {
+		match error {
+			SolveError::Infeasible { .. } => PruneReason::Infeasible,
+			error => PruneReason::Failed(error),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct PrunedNode {
+	pub section: String,
+	pub reason: PruneReason,
+}
+
+/// Best integer solution found by [`crate::branch_and_bound`], in the number type it computed with.
+#[derive(Clone, Debug)]
+pub struct Solution<T = f32> {
+	pub status: SolutionStatus,
+	/// Objective value of the best integer solution.
+	pub objective: T,
+	/// Best objective value any integer solution can have, equal to `objective` once the search is
+	/// complete and infinite when the solver can't tell.
+	pub bound: T,
+	/// Values of the `x` variables, in the order they appear in the variable map.
+	pub values: Vec<T>,
+	/// Names of the `x` variables, matching `values`.
+	pub names: Vec<String>,
+	/// Section of the node the solution came from, e.g. "0.1.1", empty for solvers without a tree.
+	pub section: String,
+	/// Every node that was closed during the search, in the order they were closed.
+	pub pruned: Vec<PrunedNode>,
+}
+
+impl<T: Number> Solution<T> {
+	pub fn infeasible() -> Self {
+		Solution {
+			status: SolutionStatus::Infeasible,
+			objective: -T::infinity(),
+			bound: -T::infinity(),
+			values: Vec::new(),
+			names: Vec::new(),
+			section: String::new(),
+			pruned: Vec::new(),
+		}
+	}
+
+	/// How far the objective value may still be from the optimal one, zero once it is proven
+	/// optimal or infeasible.
+	pub fn gap(&self) -> T {
+		if self.bound == self.objective {
+			T::zero()
+		} else {
+			(self.bound - self.objective).abs()
+		}
+	}
+
+	/// Value of the variable with the given name.
+	pub fn value(&self, name: &str) -> Option<T> {
+		self
+			.names
+			.iter()
+			.position(|n| n == name)
+			.map(|i| self.values[i])
+	}
+}
@@ -0,0 +1,194 @@
+use crate::{
+	Knapsack, Solution, SolutionStatus, SolverEvent, SolverObserver, knapsack_dp, knapsack_dp_lean,
+};
+
+/// Algorithm solving a [`Knapsack`], so different algorithms can be swapped and compared.
+pub trait KnapsackSolver {
+	/// Short unique name, used to look the solver up in a [`SolverRegistry`].
+	fn name(&self) -> &str;
+
+	/// Whether the solver can handle the problem, [`KnapsackSolver::solve`] may panic otherwise.
+	fn supports(&self, _problem: &Knapsack) -> bool {
+		true
+	}
+
+	/// Solves the problem, reporting progress to `observer`. Solvers without a search tree only
+	/// report the final solution.
+	fn solve(&self, problem: &Knapsack, observer: &mut dyn SolverObserver) -> Solution;
+}
+
+/// Simplex based branch and bound on [`Knapsack::model`], using the problem's solver options.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BranchAndBound;
+
+impl KnapsackSolver for BranchAndBound {
+	fn name(&self) -> &str {
+		"branch-and-bound"
+	}
+
+	fn solve(&self, problem: &Knapsack, observer: &mut dyn SolverObserver) -> Solution {
+		problem.model().solve_with_observer(observer)
+	}
+}
+
+/// [`knapsack_dp`], which needs non-negative integer weights and capacity.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DynamicProgramming;
+
+impl KnapsackSolver for DynamicProgramming {
+	fn name(&self) -> &str {
+		"dynamic-programming"
+	}
+
+	fn supports(&self, problem: &Knapsack) -> bool {
+		problem.integer_weights().is_some()
+	}
+
+	fn solve(&self, problem: &Knapsack, observer: &mut dyn SolverObserver) -> Solution {
+		let (weights, capacity) = expect_integer_weights(problem);
+		finish(knapsack_dp(problem.values(), &weights, capacity), observer)
+	}
+}
+
+/// [`knapsack_dp_lean`], which needs non-negative integer weights and capacity.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DynamicProgrammingLean;
+
+impl KnapsackSolver for DynamicProgrammingLean {
+	fn name(&self) -> &str {
+		"dynamic-programming-lean"
+	}
+
+	fn supports(&self, problem: &Knapsack) -> bool {
+		problem.integer_weights().is_some()
+	}
+
+	fn solve(&self, problem: &Knapsack, observer: &mut dyn SolverObserver) -> Solution {
+		let (weights, capacity) = expect_integer_weights(problem);
+		finish(
+			knapsack_dp_lean(problem.values(), &weights, capacity),
+			observer,
+		)
+	}
+}
+
+/// Picks items by decreasing value to weight ratio while they fit, and returns the better of that
+/// and the most valuable single item. Fast, and never worse than half the optimum, but the solution
+/// is only [`SolutionStatus::Feasible`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Greedy;
+
+impl KnapsackSolver for Greedy {
+	fn name(&self) -> &str {
+		"greedy"
+	}
+
+	fn solve(&self, problem: &Knapsack, observer: &mut dyn SolverObserver) -> Solution {
+		let values = problem.values();
+		let weights = problem.weights();
+		let capacity = problem.capacity();
+
+		// items without value only ever take up room
+		let mut order = (0..values.len())
+			.filter(|&i| values[i] > 0.0)
+			.collect::<Vec<_>>();
+		order.sort_by(|&a, &b| {
+			let ratio = |i: usize| values[i] / weights[i].max(0.0);
+			ratio(b).total_cmp(&ratio(a))
+		});
+
+		let mut picked = vec![false; values.len()];
+		let mut total = (0.0, 0.0);
+		for &i in &order {
+			if total.1 + weights[i] <= capacity {
+				picked[i] = true;
+				total = (total.0 + values[i], total.1 + weights[i]);
+			}
+		}
+
+		let single = order
+			.iter()
+			.copied()
+			.filter(|&i| weights[i] <= capacity)
+			.max_by(|&a, &b| values[a].total_cmp(&values[b]));
+		if let Some(i) = single
+			&& values[i] > total.0
+		{
+			picked = vec![false; values.len()];
+			picked[i] = true;
+			total = (values[i], weights[i]);
+		}
+
+		// only happens when not even the empty knapsack fits
+		let solution = if total.1 > capacity {
+			Solution::infeasible()
+		} else {
+			Solution {
+				status: SolutionStatus::Feasible,
+				objective: total.0,
+				// greedy can't bound how much better the optimum is
+				bound: f32::INFINITY,
+				values: picked.iter().map(|&p| if p { 1.0 } else { 0.0 }).collect(),
+				names: (1..=values.len()).map(|i| format!("x{i}")).collect(),
+				section: String::new(),
+				pruned: Vec::new(),
+			}
+		};
+
+		finish(solution, observer)
+	}
+}
+
+fn expect_integer_weights(problem: &Knapsack) -> (Vec<usize>, usize) {
+	problem
+		.integer_weights()
+		.expect("dynamic programming needs non-negative integer weights and capacity")
+}
+
+fn finish(solution: Solution, observer: &mut dyn SolverObserver) -> Solution {
+	observer.on_event(&SolverEvent::Finished {
+		solution: &solution,
+	});
+	solution
+}
+
+/// Named collection of solvers, e.g. to run every algorithm on the same problem.
+pub struct SolverRegistry {
+	solvers: Vec<Box<dyn KnapsackSolver + Send + Sync>>,
+}
+
+impl SolverRegistry {
+	/// Registry without any solvers, see [`SolverRegistry::default`] for the built-in ones.
+	pub fn empty() -> Self {
+		SolverRegistry {
+			solvers: Vec::new(),
+		}
+	}
+
+	/// Adds a solver, replacing any solver registered under the same name.
+	pub fn register(&mut self, solver: impl KnapsackSolver + Send + Sync + 'static) {
+		self.solvers.retain(|s| s.name() != solver.name());
+		self.solvers.push(Box::new(solver));
+	}
+
+	pub fn get(&self, name: &str) -> Option<&(dyn KnapsackSolver + Send + Sync)> {
+		self.solvers.iter().find(|s| s.name() == name).map(|s| &**s)
+	}
+
+	/// Solvers in the order they were registered.
+	pub fn iter(&self) -> impl Iterator<Item = &(dyn KnapsackSolver + Send + Sync)> {
+		self.solvers.iter().map(|s| &**s)
+	}
+}
+
+/// Registry with every built-in solver.
+impl Default for SolverRegistry {
+	fn default() -> Self {
+		let mut registry = SolverRegistry::empty();
+		registry.register(BranchAndBound);
+		registry.register(DynamicProgramming);
+		registry.register(DynamicProgrammingLean);
+		registry.register(Greedy);
+		registry
+	}
+}
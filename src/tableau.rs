@@ -0,0 +1,184 @@
+use crate::{
+	Bounds, Number, PruneReason, PrunedNode, Sense, Solution, SolutionStatus, SolveError,
+	SolverEvent, SolverObserver, SolverOptions, VariableInfo, branch_and_bound, get_bounded_values,
+	get_optimal_two_phase,
+};
+use matrix::format::Compressed;
+use std::time::{Duration, Instant};
+
+/// Standard form tableau along with the variable each column represents. The objective row holds
+/// `z - c·x = 0` in the problem's own sense and is converted to maximisation form when solving.
+#[derive(Clone)]
+pub struct Tableau<T: Number = f32> {
+	pub sense: Sense,
+	/// Number of decision variables, which occupy the first columns.
+	pub var_count: i32,
+	pub con_coef: Compressed<T>,
+	pub con_rhs_coef: Compressed<T>,
+	pub obj_coef: Compressed<T>,
+	pub obj_rhs: Compressed<T>,
+	/// Bounds of every column, the rows already account for columns measured from a non-zero
+	/// bound.
+	pub bounds: Bounds<T>,
+	/// Basic column of every row, `None` for rows that need an artificial variable to start from,
+	/// see [`crate::get_basis`] for tableaux built by hand.
+	pub basis: Vec<Option<usize>>,
+	pub variable_map: Vec<VariableInfo>,
+}
+
+impl<T: Number> Tableau<T> {
+	/// Value of every variable, decision variables and slacks alike, alongside its name. Basic
+	/// variables take their row's right-hand side and the others sit at a bound.
+	pub fn primal_values(&self) -> Vec<(&str, T)> {
+		get_bounded_values(&self.con_rhs_coef, &self.bounds, &self.basis)
+			.into_iter()
+			.zip(&self.variable_map)
+			.map(|(value, variable)| (variable.name.as_str(), value))
+			.collect()
+	}
+
+	/// Copy of the tableau with the objective row in maximisation form, which the simplex expects.
+	pub(crate) fn maximising(&self) -> Self {
+		let mut tableau = self.clone();
+		if self.sense == Sense::Minimise {
+			// minimising z is maximising -z
+			for value in tableau.obj_coef.values.iter_mut() {
+				*value = -*value;
+			}
+
+			tableau.obj_rhs.set((0, 0), -tableau.obj_rhs.get((0, 0)));
+		}
+
+		tableau
+	}
+
+	/// Solves the relaxation with the two-phase simplex and runs branch and bound on it, reporting
+	/// progress to `observer`.
+	pub fn solve(
+		&self,
+		options: &SolverOptions,
+		observer: &mut dyn SolverObserver<T>,
+	) -> Solution<T> {
+		let mut root = self.maximising();
+		let start = Instant::now();
+		let result = get_optimal_two_phase(&mut root, options);
+
+		let iterations = match result {
+			Ok(iterations) => iterations,
+			Err(error) => {
+				let reason = PruneReason::from(error);
+				observer.on_event(&SolverEvent::NodeOpened {
+					section: "0",
+					depth: 0,
+					bound: self.sense.signum::<T>() * T::infinity(),
+				});
+				observer.on_event(&SolverEvent::LpFailed {
+					section: "0",
+					error,
+					time: start.elapsed(),
+				});
+				observer.on_event(&SolverEvent::NodePruned {
+					section: "0",
+					reason: &reason,
+					bound: None,
+				});
+
+				let mut solution = Solution::infeasible();
+				match error {
+					SolveError::Infeasible { .. } => {}
+					SolveError::Unbounded { .. } => {
+						solution.status = SolutionStatus::Unbounded;
+						solution.objective = T::infinity();
+						solution.bound = T::infinity();
+					}
+					// giving up on the root relaxation proves nothing about the problem
+					SolveError::Cancelled { .. } => {
+						solution.status = SolutionStatus::Cancelled;
+						solution.bound = T::infinity();
+					}
+					SolveError::IterationLimit { .. } => {
+						solution.status = SolutionStatus::LimitReached;
+						solution.bound = T::infinity();
+					}
+					SolveError::Numerical { .. } => {
+						solution.status = SolutionStatus::Failed(error);
+						solution.bound = T::infinity();
+					}
+				}
+
+				solution.objective *= self.sense.signum::<T>();
+				solution.bound *= self.sense.signum::<T>();
+				solution.pruned.push(PrunedNode {
+					section: "0".to_string(),
+					reason,
+				});
+
+				observer.on_event(&SolverEvent::Finished {
+					solution: &solution,
+				});
+				return solution;
+			}
+		};
+
+		// the root is reported once branch and bound opens it, like every other node
+		let mut observer = RootSolved {
+			observer,
+			solved: Some(RootLp {
+				objective: self.sense.signum::<T>() * root.obj_rhs.get((0, 0)),
+				values: get_bounded_values(&root.con_rhs_coef, &root.bounds, &root.basis),
+				variables: root.variable_map.clone(),
+				iterations,
+				time: start.elapsed(),
+			}),
+		};
+
+		branch_and_bound(root, options, &mut observer)
+	}
+}
+
+/// Root relaxation solved before branch and bound starts.
+struct RootLp<T> {
+	objective: T,
+	values: Vec<T>,
+	variables: Vec<VariableInfo>,
+	iterations: usize,
+	time: Duration,
+}
+
+/// Holds back the root's [`SolverEvent::LpSolved`] until branch and bound opens the root, or
+/// finishes without opening it.
+struct RootSolved<'a, T: Number> {
+	observer: &'a mut dyn SolverObserver<T>,
+	solved: Option<RootLp<T>>,
+}
+
+impl<T: Number> RootSolved<'_, T> {
+	fn report(&mut self) {
+		if let Some(root) = self.solved.take() {
+			self.observer.on_event(&SolverEvent::LpSolved {
+				section: "0",
+				objective: root.objective,
+				values: &root.values,
+				variables: &root.variables,
+				iterations: root.iterations,
+				time: root.time,
+			});
+		}
+	}
+}
+
+impl<T: Number> SolverObserver<T> for RootSolved<'_, T> {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		match event {
+			SolverEvent::NodeOpened { section: "0", .. } => {
+				self.observer.on_event(event);
+				self.report();
+			}
+			SolverEvent::Finished { .. } => {
+				self.report();
+				self.observer.on_event(event);
+			}
+			_ => self.observer.on_event(event),
+		}
+	}
+}
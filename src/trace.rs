@@ -0,0 +1,261 @@
+use crate::{BranchDirection, Number, PruneReason, SolutionStatus, SolverEvent, SolverObserver};
+use std::{
+	collections::HashMap,
+	io::{self, Write},
+	time::{Duration, Instant},
+};
+
+/// Everything known about a node until it is closed, with numbers already written as JSON.
+#[derive(Default)]
+struct Record {
+	parent: Option<String>,
+	/// Variable, direction and right-hand side of the bound that created the node.
+	branch: Option<(String, BranchDirection, String)>,
+	objective: Option<String>,
+	names: Vec<String>,
+	values: Option<String>,
+	iterations: usize,
+	lp_time: Duration,
+	error: Option<String>,
+}
+
+/// Writes the solve as JSON Lines, one object per node once the node is closed and a final
+/// `"solution"` object.
+///
+/// Node objects hold the `section` and `parent`, the `branch` that created the node, the relaxation
+/// `objective`, the `names` and `values` of every column, the `pivots` and `lp_time` in seconds the
+/// simplex needed, the `elapsed` seconds since the trace was created and the node's `fate`, which is
+/// one of `branched`, `integral`, `pruned`, `infeasible` or `failed`. Branched nodes also name the
+/// variable they were `branched_on`. The solution object holds the `status`, the `objective` and
+/// `bound` of the search and the `names` and `values` of the best solution.
+///
+/// Like [`crate::TextLog`], the first write error is kept and returned by [`JsonTrace::finish`].
+pub struct JsonTrace<W: Write> {
+	writer: W,
+	error: Option<io::Error>,
+	start: Instant,
+	open: HashMap<String, Record>,
+}
+
+impl<W: Write> JsonTrace<W> {
+	pub fn new(writer: W) -> Self {
+		JsonTrace {
+			writer,
+			error: None,
+			start: Instant::now(),
+			open: HashMap::new(),
+		}
+	}
+
+	/// Flushes the writer and returns the first error met while writing.
+	pub fn finish(mut self) -> io::Result<W> {
+		if let Some(error) = self.error.take() {
+			return Err(error);
+		}
+
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+
+	fn record(&mut self, section: &str) -> &mut Record {
+		self.open.entry(section.to_string()).or_default()
+	}
+
+	fn close(
+		&mut self,
+		section: &str,
+		fate: &str,
+		branched_on: Option<(&str, String)>,
+	) -> io::Result<()> {
+		let record = self.open.remove(section).unwrap_or_default();
+
+		let mut line = format!("{{\"event\":\"node\",\"section\":{}", string(section));
+		line += &format!(
+			",\"parent\":{}",
+			record.parent.as_deref().map_or("null".to_string(), string)
+		);
+
+		match &record.branch {
+			Some((variable, direction, bound)) => {
+				let direction = match direction {
+					BranchDirection::Down => "down",
+					BranchDirection::Up => "up",
+				};
+
+				line += &format!(
+					",\"branch\":{{\"variable\":{},\"direction\":\"{direction}\",\"bound\":{}}}",
+					string(variable),
+					bound
+				);
+			}
+			None => line += ",\"branch\":null",
+		}
+
+		line += &format!(
+			",\"objective\":{}",
+			record.objective.as_deref().unwrap_or("null")
+		);
+		line += &format!(
+			",\"names\":{},\"values\":{}",
+			strings(&record.names),
+			record.values.as_deref().unwrap_or("[]")
+		);
+		line += &format!(
+			",\"pivots\":{},\"lp_time\":{},\"elapsed\":{}",
+			record.iterations,
+			record.lp_time.as_secs_f64(),
+			self.start.elapsed().as_secs_f64()
+		);
+		line += &format!(",\"fate\":\"{fate}\"");
+
+		if let Some((variable, value)) = branched_on {
+			line += &format!(
+				",\"branched_on\":{{\"variable\":{},\"value\":{}}}",
+				string(variable),
+				value
+			);
+		}
+
+		if let Some(error) = &record.error {
+			line += &format!(",\"error\":{}", string(error));
+		}
+
+		writeln!(self.writer, "{line}}}")
+	}
+
+	fn write<T: Number>(&mut self, event: &SolverEvent<T>) -> io::Result<()> {
+		match *event {
+			SolverEvent::BranchCreated {
+				parent,
+				section,
+				variable,
+				direction,
+				bound,
+			} => {
+				let record = self.record(section);
+				record.parent = Some(parent.to_string());
+				record.branch = Some((variable.to_string(), direction, number(bound)));
+			}
+			SolverEvent::LpSolved {
+				section,
+				objective,
+				values,
+				variables,
+				iterations,
+				time,
+			} => {
+				let record = self.record(section);
+				record.objective = Some(number(objective));
+				record.names = variables.iter().map(|v| v.name.clone()).collect();
+				record.values = Some(numbers(values));
+				record.iterations = iterations;
+				record.lp_time = time;
+			}
+			SolverEvent::LpFailed {
+				section,
+				error,
+				time,
+			} => {
+				let record = self.record(section);
+				record.iterations = error.iterations();
+				record.lp_time = time;
+				record.error = Some(error.to_string());
+			}
+			SolverEvent::Branching {
+				section,
+				variable,
+				value,
+				..
+			} => self.close(section, "branched", Some((variable, number(value))))?,
+			SolverEvent::NodePruned {
+				section, reason, ..
+			} => {
+				let fate = match reason {
+					PruneReason::Bound => "pruned",
+					PruneReason::Infeasible => "infeasible",
+					PruneReason::Integral => "integral",
+					PruneReason::Failed(_) => "failed",
+				};
+
+				self.close(section, fate, None)?;
+			}
+			SolverEvent::Finished { solution } => {
+				let status = match solution.status {
+					SolutionStatus::Optimal => "optimal",
+					SolutionStatus::Feasible => "feasible",
+					SolutionStatus::Infeasible => "infeasible",
+					SolutionStatus::Unbounded => "unbounded",
+					SolutionStatus::Failed(_) => "failed",
+					SolutionStatus::LimitReached => "limit_reached",
+					SolutionStatus::Cancelled => "cancelled",
+				};
+
+				writeln!(
+					self.writer,
+					"{{\"event\":\"solution\",\"status\":\"{status}\",\"section\":{},\"objective\":{},\"bound\":{},\"names\":{},\"values\":{},\"elapsed\":{}}}",
+					string(&solution.section),
+					number(solution.objective),
+					number(solution.bound),
+					strings(&solution.names),
+					numbers(&solution.values),
+					self.start.elapsed().as_secs_f64()
+				)?;
+			}
+			_ => {}
+		}
+
+		Ok(())
+	}
+}
+
+impl<T: Number, W: Write> SolverObserver<T> for JsonTrace<W> {
+	fn on_event(&mut self, event: &SolverEvent<T>) {
+		if self.error.is_none()
+			&& let Err(error) = self.write(event)
+		{
+			self.error = Some(error);
+		}
+	}
+}
+
+/// JSON string literal.
+fn string(value: &str) -> String {
+	let mut result = String::with_capacity(value.len() + 2);
+	result.push('"');
+	for c in value.chars() {
+		match c {
+			'"' => result += "\\\"",
+			'\\' => result += "\\\\",
+			'\n' => result += "\\n",
+			'\r' => result += "\\r",
+			'\t' => result += "\\t",
+			c if (c as u32) < 0x20 => result += &format!("\\u{:04x}", c as u32),
+			c => result.push(c),
+		}
+	}
+
+	result.push('"');
+	result
+}
+
+/// JSON number, JSON has no infinities or NaN so those become `null`. Exact types are written as
+/// their closest `f64` since JSON has no fractions either.
+fn number<T: Number>(value: T) -> String {
+	if !value.is_finite() {
+		"null".to_string()
+	} else if T::EXACT {
+		value.to_f64().to_string()
+	} else {
+		value.to_string()
+	}
+}
+
+fn strings(values: &[String]) -> String {
+	let items = values.iter().map(|v| string(v)).collect::<Vec<_>>();
+	format!("[{}]", items.join(","))
+}
+
+fn numbers<T: Number>(values: &[T]) -> String {
+	let items = values.iter().map(|&v| number(v)).collect::<Vec<_>>();
+	format!("[{}]", items.join(","))
+}
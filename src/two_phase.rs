@@ -0,0 +1,283 @@
+use crate::{
+	Number, SolveError, SolverOptions, Tableau, VariableInfo, checked_pivot, continue_primal,
+};
+use matrix::{Matrix, format::Compressed};
+use std::mem;
+
+/// Copy of the matrix without the given row, used to drop redundant rows.
+fn without_row<T: Number>(matrix: &Compressed<T>, row: usize) -> Compressed<T> {
+	let mut result = Compressed::<T>::zero((matrix.rows - 1, matrix.columns));
+	for i in (0..matrix.rows).filter(|&i| i != row) {
+		let target = if i > row { i - 1 } else { i };
+		// every element is set since the simplex expects right-hand side values to line up with rows
+		for j in 0..matrix.columns {
+			result.set((target, j), matrix.get((i, j)));
+		}
+	}
+
+	result
+}
+
+/// Finds a feasible basis for a tableau whose rows have no obvious starting basis, such as rows with
+/// a negative right-hand side or rows without a slack column. The tableau's basis holds the basic
+/// column of every row, or `None` for rows without one, and holds the feasible basis once this
+/// returns.
+///
+/// Rows with a negative right-hand side are negated and every row without a basic column whose
+/// range fits the right-hand side gets an artificial variable. The sum of the artificials is then
+/// minimised, any artificial left in the basis at zero is pivoted out, rows where that is impossible
+/// are redundant and are dropped, and the artificial columns are removed. Returns the number of
+/// pivots performed.
+///
+/// The objective row is untouched, it has to be re-expressed in terms of the new basis before
+/// optimising, which [`get_optimal_two_phase`] does.
+pub fn get_feasible_basis<T: Number>(
+	tableau: &mut Tableau<T>,
+	options: &SolverOptions,
+) -> Result<usize, SolveError> {
+	let columns = tableau.con_coef.columns;
+	let con_coef = &mut tableau.con_coef;
+	let con_rhs_coef = &mut tableau.con_rhs_coef;
+	for (i, basic) in tableau.basis.iter_mut().enumerate() {
+		if con_rhs_coef.get((i, 0)) < T::zero() {
+			for j in 0..columns {
+				let value = con_coef.get((i, j));
+				if !value.is_zero() {
+					con_coef.set((i, j), -value);
+				}
+			}
+
+			con_rhs_coef.set((i, 0), -con_rhs_coef.get((i, 0)));
+			// its basic column is a negative unit column now
+			*basic = None;
+		}
+	}
+
+	let artificial_rows = (0..con_coef.rows)
+		.filter(|&i| {
+			let rhs = con_rhs_coef.get((i, 0));
+			tableau.basis[i].is_none_or(|j| tableau.bounds.range(j) < rhs)
+		})
+		.collect::<Vec<_>>();
+
+	con_coef.resize((con_coef.rows, columns + artificial_rows.len()));
+	tableau.bounds.resize(con_coef.columns);
+	for (k, &i) in artificial_rows.iter().enumerate() {
+		con_coef.set((i, columns + k), T::one());
+		tableau.basis[i] = Some(columns + k);
+		tableau.variable_map.push(VariableInfo::new("a", k));
+	}
+
+	// maximise the negated artificial sum, written in terms of the starting basis by subtracting
+	// every artificial row from the objective row
+	let mut phase_obj = Compressed::<T>::zero((1, con_coef.columns));
+	let mut phase_rhs = Compressed::<T>::zero((1, 1));
+	for j in 0..columns {
+		let value = artificial_rows
+			.iter()
+			.fold(T::zero(), |sum, &i| sum + con_coef.get((i, j)));
+		phase_obj.set((0, j), -value);
+	}
+
+	phase_rhs.set(
+		(0, 0),
+		-artificial_rows
+			.iter()
+			.fold(T::zero(), |sum, &i| sum + con_rhs_coef.get((i, 0))),
+	);
+
+	// the phase one objective stands in for the real one, which is put back untouched
+	let obj_coef = mem::replace(&mut tableau.obj_coef, phase_obj);
+	let obj_rhs = mem::replace(&mut tableau.obj_rhs, phase_rhs);
+	let result = remove_artificials(tableau, columns, &artificial_rows, options);
+	tableau.obj_coef = obj_coef;
+	tableau.obj_rhs = obj_rhs;
+
+	tableau.con_coef.resize((tableau.con_coef.rows, columns));
+	tableau.bounds.resize(columns);
+	tableau.variable_map.truncate(columns);
+	result
+}
+
+/// Phase one proper, minimising the artificials in the columns from `columns` on and driving the
+/// ones left at zero out of the basis.
+fn remove_artificials<T: Number>(
+	tableau: &mut Tableau<T>,
+	columns: usize,
+	artificial_rows: &[usize],
+	options: &SolverOptions,
+) -> Result<usize, SolveError> {
+	let tolerances = &options.tolerances;
+	let mut iterations = continue_primal(tableau, options, 0, &mut ())?;
+
+	if tableau.obj_rhs.get((0, 0)) < -T::tolerance(tolerances.feasibility) {
+		let row = (0..tableau.con_coef.rows)
+			.find(|&i| {
+				tableau.basis[i].is_some_and(|j| j >= columns)
+					&& tableau.con_rhs_coef.get((i, 0)) > T::tolerance(tolerances.feasibility)
+			})
+			.unwrap_or(artificial_rows[0]);
+
+		return Err(SolveError::Infeasible { row, iterations });
+	}
+
+	// drive artificials that are still basic at zero out of the basis
+	let mut i = 0;
+	while i < tableau.con_coef.rows {
+		if tableau.basis[i].is_none_or(|j| j < columns) {
+			i += 1;
+			continue;
+		}
+
+		let pivot = T::tolerance(tolerances.pivot);
+		match (0..columns).find(|&j| tableau.con_coef.get((i, j)).abs() > pivot) {
+			Some(enter) => {
+				checked_pivot(tableau, enter as i32, i as i32, &mut iterations, options)?;
+				i += 1;
+			}
+			None => {
+				tableau.con_coef = without_row(&tableau.con_coef, i);
+				tableau.con_rhs_coef = without_row(&tableau.con_rhs_coef, i);
+				tableau.basis.remove(i);
+			}
+		}
+	}
+
+	Ok(iterations)
+}
+
+/// Two-phase simplex, finding a feasible basis with [`get_feasible_basis`] before optimising the
+/// objective with the primal simplex. The tableau's basis holds the basic column of every row, as
+/// for [`get_feasible_basis`]. Returns the total number of pivots performed.
+pub fn get_optimal_two_phase<T: Number>(
+	tableau: &mut Tableau<T>,
+	options: &SolverOptions,
+) -> Result<usize, SolveError> {
+	let flipped = tableau.bounds.flipped.clone();
+	let iterations = get_feasible_basis(tableau, options)?;
+
+	// express the objective in terms of the basis, every column is set so that the objective values
+	// line up with the columns
+	let mut objective = (0..tableau.con_coef.columns)
+		.map(|j| tableau.obj_coef.get((0, j)))
+		.collect::<Vec<_>>();
+	let mut objective_rhs = tableau.obj_rhs.get((0, 0));
+
+	// columns phase one moved to their other bound now stand for `range - column`
+	for (j, value) in objective.iter_mut().enumerate() {
+		if tableau.bounds.flipped[j] != flipped[j] {
+			objective_rhs -= *value * tableau.bounds.range(j);
+			*value = -*value;
+		}
+	}
+
+	for (i, &basic) in tableau.basis.iter().enumerate() {
+		let Some(basic) = basic else {
+			continue;
+		};
+
+		let factor = objective[basic];
+		if factor.is_zero() {
+			continue;
+		}
+
+		for (j, value) in objective.iter_mut().enumerate() {
+			*value -= factor * tableau.con_coef.get((i, j));
+		}
+
+		objective_rhs -= factor * tableau.con_rhs_coef.get((i, 0));
+	}
+
+	tableau.obj_coef = Compressed::<T>::zero((1, tableau.con_coef.columns));
+	for (j, value) in objective.into_iter().enumerate() {
+		tableau.obj_coef.set((0, j), value);
+	}
+
+	tableau.obj_rhs.set((0, 0), objective_rhs);
+	continue_primal(tableau, options, iterations, &mut ())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Model, Number, Rational, Relation, Sense, Tableau};
+
+	/// Lowers the model and solves its relaxation with both phases.
+	fn solve(model: &Model<Rational>) -> Result<Tableau<Rational>, SolveError> {
+		let mut tableau = model.tableau().maximising();
+		get_optimal_two_phase(&mut tableau, &SolverOptions::default())?;
+		Ok(tableau)
+	}
+
+	fn value(tableau: &Tableau<Rational>, name: &str) -> Rational {
+		tableau
+			.primal_values()
+			.into_iter()
+			.find(|&(n, _)| n == name)
+			.map(|(_, value)| value)
+			.unwrap()
+	}
+
+	#[test]
+	fn rows_without_a_starting_basis() {
+		// maximise 2x + y subject to x + y >= 2, x - y = 1, x - 3y <= -1 and x + y <= 7, which
+		// leaves y between 1 and 3 with x = y + 1
+		let mut model = Model::<Rational>::default();
+		let x = model.add_variable("x", 0.into(), Rational::infinity(), false);
+		let y = model.add_variable("y", 0.into(), Rational::infinity(), false);
+		let row = |a: i64, b: i64| [(x, a.into()), (y, b.into())];
+		model.add_constraint("cover", &row(1, 1), Relation::GreaterEqual, 2.into());
+		model.add_constraint("link", &row(1, -1), Relation::Equal, 1.into());
+		model.add_constraint("negative", &row(1, -3), Relation::LessEqual, (-1).into());
+		model.add_constraint("limit", &row(1, 1), Relation::LessEqual, 7.into());
+		model.set_objective(Sense::Maximise, &row(2, 1));
+
+		let tableau = solve(&model).unwrap();
+		assert_eq!(tableau.obj_rhs.get((0, 0)), 11.into());
+		assert_eq!(value(&tableau, "x"), 4.into());
+		assert_eq!(value(&tableau, "y"), 3.into());
+		assert!(tableau.basis.iter().all(Option::is_some));
+	}
+
+	#[test]
+	fn infeasible_rows() {
+		let mut model = Model::<Rational>::default();
+		let x = model.add_variable("x", 0.into(), Rational::infinity(), false);
+		let y = model.add_variable("y", 0.into(), Rational::infinity(), false);
+		let terms = [(x, 1.into()), (y, 1.into())];
+		model.add_constraint("at least", &terms, Relation::GreaterEqual, 5.into());
+		model.add_constraint("at most", &terms, Relation::LessEqual, 3.into());
+		model.set_objective(Sense::Maximise, &[(x, 1.into())]);
+
+		assert!(matches!(solve(&model), Err(SolveError::Infeasible { .. })));
+	}
+
+	#[test]
+	fn redundant_equality_row_is_dropped() {
+		// the second row is twice the first
+		let mut model = Model::<Rational>::default();
+		let x = model.add_variable("x", 0.into(), Rational::infinity(), false);
+		let y = model.add_variable("y", 0.into(), Rational::infinity(), false);
+		model.add_constraint(
+			"once",
+			&[(x, 1.into()), (y, 1.into())],
+			Relation::Equal,
+			2.into(),
+		);
+		model.add_constraint(
+			"twice",
+			&[(x, 2.into()), (y, 2.into())],
+			Relation::Equal,
+			4.into(),
+		);
+		model.set_objective(Sense::Maximise, &[(x, 3.into()), (y, 1.into())]);
+
+		let tableau = solve(&model).unwrap();
+		assert_eq!(tableau.con_coef.rows, 1);
+		assert_eq!(tableau.con_rhs_coef.rows, 1);
+		assert_eq!(tableau.basis.len(), 1);
+		assert_eq!(tableau.con_coef.columns, 2);
+		assert_eq!(tableau.obj_rhs.get((0, 0)), 6.into());
+		assert_eq!(value(&tableau, "x"), 2.into());
+	}
+}